@@ -10,4 +10,5 @@ pub struct DebugReadFileResult {
 
 pub type DebugPlatformReadEntireFile = fn(&mut ThreadContext, &str) -> Option<DebugReadFileResult>;
 pub type DebugPlatformFreeFileMemory = fn(&mut ThreadContext, *mut c_void);
-pub type DebugPlatformWriteEntireFile = fn(&mut ThreadContext, &str, u32, *mut c_void) -> bool;
\ No newline at end of file
+pub type DebugPlatformWriteEntireFile = fn(&mut ThreadContext, &str, u32, *mut c_void) -> bool;
+pub type DebugPlatformLogMessage = fn(&mut ThreadContext, &str);
\ No newline at end of file
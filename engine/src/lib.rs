@@ -15,14 +15,20 @@ pub use sound::*;
 
 #[cfg(feature="gol-slow")]
 #[macro_export]
-macro_rules! gol_assert { ($e:expr) => { if !$e { panic!("assert!") } }; }
+macro_rules! gol_assert {
+    ($e:expr) => { if !$e { panic!("assert!") } };
+    ($e:expr, $($arg:tt)+) => { if !$e { panic!($($arg)+) } };
+}
 
 #[macro_export]
 macro_rules! invalid_code_path { () => { gol_assert!(1 == 1); } }
 
 #[cfg(not(feature="gol-slow"))]
 #[macro_export]
-macro_rules! gol_assert { ($e:expr) => { () }; }
+macro_rules! gol_assert {
+    ($e:expr) => { () };
+    ($e:expr, $($arg:tt)+) => { () };
+}
 
 pub const PI32: f32 = 3.14159265359;
 
@@ -30,6 +30,7 @@ pub struct GameMemory {
     pub debug_platform_read_entire_file_func: Box<DebugPlatformReadEntireFile>, 
     pub debug_platform_free_file_memory_func: Box<DebugPlatformFreeFileMemory>, 
     pub debug_platform_write_entire_file_func: Box<DebugPlatformWriteEntireFile>, 
+    pub debug_platform_log_message_func: Box<DebugPlatformLogMessage>, 
 }
 
 pub fn initialised(game_memory: &GameMemory) -> bool {
@@ -61,6 +62,10 @@ impl GameMemory {
     pub fn debug_platform_write_entire_file(&self, thread_context: &mut ThreadContext, file_name: &str, memory_size: u32, memory: *mut c_void) -> bool {
         (self.debug_platform_write_entire_file_func)(thread_context, file_name, memory_size, memory)
     }
+     
+    pub fn debug_platform_log_message(&self, thread_context: &mut ThreadContext, message: &str) {
+        (self.debug_platform_log_message_func)(thread_context, message)
+    }
 }
 
 pub trait MemoryChunkFactory {
@@ -1,5 +1,9 @@
 #[repr(C)]
 #[derive(Default)]
 pub struct ThreadContext {
-    pub placeholder: u32
-}
\ No newline at end of file
+    pub worker_index: u32
+}
+
+pub fn worker_thread_context(worker_index: u32) -> ThreadContext {
+    ThreadContext { worker_index }
+}
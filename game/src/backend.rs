@@ -0,0 +1,155 @@
+use gol_engine::ThreadContext;
+
+use crate::{ecs::*, bitgrid::*, hashlife::*, systems::*, CellPosition, CellState, GridCoordinate};
+use crate::{get_cell_at, grid_coordinate, spawn_cell};
+
+pub trait LifeBackend {
+    fn set_cell(&mut self, coordinate: GridCoordinate, alive: bool) -> bool;
+    fn is_cell_alive_at(&self, coordinate: GridCoordinate) -> bool;
+    fn live_cells(&self) -> Vec<GridCoordinate>;
+    fn step_generation(&mut self);
+    fn generation(&self) -> u64;
+}
+
+impl LifeBackend for World {
+    fn set_cell(&mut self, coordinate: GridCoordinate, alive: bool) -> bool {
+        spawn_cell(self, coordinate, alive).is_some()
+    }
+
+    fn is_cell_alive_at(&self, coordinate: GridCoordinate) -> bool {
+        get_cell_at(self, coordinate).is_some_and(|cell| is_cell_alive(self, cell))
+    }
+
+    fn live_cells(&self) -> Vec<GridCoordinate> {
+        let mut live: Vec<GridCoordinate> = iterate_query::<CellPosition, CellState>(self)
+            .filter(|(_, state)| state.alive)
+            .map(|(position, _)| grid_coordinate(position))
+            .collect();
+        live.sort_by_key(|(x, y)| (*y, *x));
+        live
+    }
+
+    fn step_generation(&mut self) {
+        maintain_frontier(self);
+        step_life(&ThreadContext::default(), self);
+    }
+
+    fn generation(&self) -> u64 {
+        life_generation(self)
+    }
+}
+
+impl LifeBackend for BitGrid {
+    fn set_cell(&mut self, coordinate: GridCoordinate, alive: bool) -> bool {
+        set_bit_grid_cell(self, coordinate, alive)
+    }
+
+    fn is_cell_alive_at(&self, coordinate: GridCoordinate) -> bool {
+        get_bit_grid_cell(self, coordinate)
+    }
+
+    fn live_cells(&self) -> Vec<GridCoordinate> {
+        bit_grid_live_cells(self)
+    }
+
+    fn step_generation(&mut self) {
+        step_bit_grid(self);
+    }
+
+    fn generation(&self) -> u64 {
+        bit_grid_generation(self)
+    }
+}
+
+impl LifeBackend for HashLife {
+    fn set_cell(&mut self, coordinate: GridCoordinate, alive: bool) -> bool {
+        set_hashlife_cell(self, coordinate, alive).is_ok()
+    }
+
+    fn is_cell_alive_at(&self, coordinate: GridCoordinate) -> bool {
+        get_hashlife_cell(self, coordinate)
+    }
+
+    fn live_cells(&self) -> Vec<GridCoordinate> {
+        hashlife_live_cells(self)
+    }
+
+    fn step_generation(&mut self) {
+        step_hashlife(self, 1).unwrap();
+    }
+
+    fn generation(&self) -> u64 {
+        hashlife_generation(self)
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn seed_soup(backend: &mut dyn LifeBackend, columns: std::ops::Range<i64>, rows: std::ops::Range<i64>, seed: u64) {
+    let mut state = seed;
+    for y in rows {
+        for x in columns.clone() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            backend.set_cell((x, y), state.is_multiple_of(3));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{conway_rule, fill_bounded_board, parse_rule, Neighbourhood, Rule, Topology, Twist};
+
+    fn create_ecs_backend(topology: Topology, rule: Rule, neighbourhood: Neighbourhood) -> World {
+        let mut world = create_world();
+        add_resource(&mut world, topology);
+        add_resource(&mut world, rule);
+        add_resource(&mut world, neighbourhood);
+        fill_bounded_board(&mut world);
+        world
+    }
+
+    #[test]
+    fn dense_and_ecs_backends_agree() {
+        let high_life = parse_rule("B36/S23").unwrap();
+        let cases = [
+            (Topology::Torus { width: 70, height: 12 }, conway_rule(), Neighbourhood::Moore),
+            (Topology::Plane { width: 20, height: 15 }, high_life, Neighbourhood::Moore),
+            (Topology::KleinBottle { width: 16, height: 14, twist: Twist::Width }, conway_rule(), Neighbourhood::Moore),
+            (Topology::CrossSurface { width: 15, height: 16 }, conway_rule(), Neighbourhood::Moore),
+            (Topology::Torus { width: 18, height: 18 }, parse_rule("B2/S").unwrap(), Neighbourhood::VonNeumann),
+        ];
+        for (seed, (topology, rule, neighbourhood)) in cases.into_iter().enumerate() {
+            let (width, height) = crate::topology_size(&topology).unwrap();
+            let mut ecs = create_ecs_backend(topology, rule, neighbourhood);
+            let mut dense = bit_grid(topology, rule, neighbourhood).unwrap();
+            seed_soup(&mut ecs, 0..width, 0..height, seed as u64 + 1);
+            seed_soup(&mut dense, 0..width, 0..height, seed as u64 + 1);
+
+            for generation in 0..12 {
+                assert_eq!(ecs.live_cells(), dense.live_cells(), "{}{} generation {}", rule, topology, generation);
+                ecs.step_generation();
+                dense.step_generation();
+            }
+            assert_eq!(ecs.generation(), dense.generation());
+        }
+    }
+
+    #[test]
+    fn hashlife_and_dense_backends_agree_away_from_the_edges() {
+        for (seed, (rule, neighbourhood)) in [(conway_rule(), Neighbourhood::Moore), (parse_rule("B2/S").unwrap(), Neighbourhood::VonNeumann)].into_iter().enumerate() {
+            let mut dense = bit_grid(Topology::Plane { width: 64, height: 64 }, rule, neighbourhood).unwrap();
+            let mut hashlife = hashlife(rule, neighbourhood).unwrap();
+            seed_soup(&mut dense, 24..40, 24..40, seed as u64 + 1);
+            seed_soup(&mut hashlife, 24..40, 24..40, seed as u64 + 1);
+
+            for generation in 0..20 {
+                assert_eq!(hashlife.live_cells(), dense.live_cells(), "{} generation {}", rule, generation);
+                hashlife.step_generation();
+                dense.step_generation();
+            }
+            assert_eq!(hashlife.generation(), dense.generation());
+        }
+    }
+}
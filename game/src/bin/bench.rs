@@ -0,0 +1,201 @@
+use std::{
+    env,
+    fs,
+    hint::black_box,
+    process,
+    sync::Arc,
+    time::Instant
+};
+
+use gol_engine::ThreadContext;
+use gol_game::{*, ecs::*, systems::*};
+
+const DEFAULT_SIZES: [usize; 5] = [64, 128, 256, 512, 1024];
+const ALL_SIZES: [usize; 7] = [64, 128, 256, 512, 1024, 2048, 4096];
+const DEFAULT_CSV_FILE_NAME: &str = "bench_output.csv";
+const QUERY_REPEATS: usize = 5;
+const STEP_REPEATS: usize = 3;
+
+#[derive(Copy, Clone)]
+struct Seed(u64);
+#[derive(Copy, Clone)]
+struct Age(u32);
+
+struct BenchOptions {
+    sizes: Vec<usize>,
+    csv_file_name: String,
+}
+
+struct BenchResult {
+    size: usize,
+    cells: usize,
+    spawn_seconds: f64,
+    migrate_seconds: f64,
+    query_seconds: f64,
+    step_seconds: f64,
+    dense_step_seconds: f64,
+    ash_step_seconds: f64,
+}
+
+fn main() {
+    let options = parse_options();
+    println!("chunk size {} bytes", chunk_size());
+    println!("{:>6} {:>10} {:>14} {:>14} {:>14} {:>14} {:>14} {:>14}", "size", "cells", "spawn/s", "migrate ns", "query ns", "step ms", "dense step ms", "ash step ms");
+
+    let mut results = Vec::<BenchResult>::default();
+    for size in &options.sizes {
+        let result = run_bench(*size);
+        println!(
+            "{:>6} {:>10} {:>14.0} {:>14.1} {:>14.2} {:>14.2} {:>14.3} {:>14.3}",
+            format!("{}²", result.size),
+            result.cells,
+            result.cells as f64 / result.spawn_seconds,
+            nanoseconds_per_cell(result.migrate_seconds, result.cells),
+            nanoseconds_per_cell(result.query_seconds, result.cells),
+            result.step_seconds * 1000.0,
+            result.dense_step_seconds * 1000.0,
+            result.ash_step_seconds * 1000.0
+        );
+        results.push(result);
+    }
+
+    if let Err(error) = fs::write(&options.csv_file_name, format_csv(&results)) {
+        eprintln!("could not write {}: {}", options.csv_file_name, error);
+        process::exit(1);
+    }
+    println!("wrote {}", options.csv_file_name);
+}
+
+fn parse_options() -> BenchOptions {
+    let mut options = BenchOptions {
+        sizes: DEFAULT_SIZES.to_vec(),
+        csv_file_name: DEFAULT_CSV_FILE_NAME.to_string(),
+    };
+
+    let mut arguments = env::args().skip(1);
+    while let Some(argument) = arguments.next() {
+        match argument.as_str() {
+            "--all" => options.sizes = ALL_SIZES.to_vec(),
+            "--sizes" => options.sizes = arguments
+                .next()
+                .map(|sizes| sizes.split(',').filter_map(|size| size.trim().parse().ok()).collect())
+                .unwrap_or_default(),
+            "--csv" => options.csv_file_name = arguments.next().unwrap_or_else(|| usage()),
+            _ => usage(),
+        }
+    }
+    if options.sizes.is_empty() {
+        usage();
+    }
+    options
+}
+
+fn usage() -> ! {
+    eprintln!("usage: bench [--all] [--sizes 64,128,...] [--csv {}]", DEFAULT_CSV_FILE_NAME);
+    eprintln!("build with --release --no-default-features so gol-slow checks are not measured");
+    process::exit(2);
+}
+
+fn run_bench(size: usize) -> BenchResult {
+    let cells = size * size;
+    let mut world = create_world();
+
+    let start = Instant::now();
+    let entities: Vec<Entity> = (0..cells)
+        .map(|index| {
+            let entity = add_entity(&mut world);
+            add_component(&mut world, entity, Seed(index as u64));
+            entity
+        })
+        .collect();
+    let spawn_seconds = start.elapsed().as_secs_f64();
+
+    let start = Instant::now();
+    for entity in &entities {
+        add_component(&mut world, *entity, Age(0));
+    }
+    let migrate_seconds = start.elapsed().as_secs_f64();
+
+    let query_seconds = (0..QUERY_REPEATS)
+        .map(|_| {
+            let start = Instant::now();
+            let sum = iterate_query::<Seed, Age>(&world).fold(0u64, |sum, (seed, age)| sum.wrapping_add(seed.0 + age.0 as u64));
+            black_box(sum);
+            start.elapsed().as_secs_f64()
+        })
+        .fold(f64::MAX, f64::min);
+    drop(world);
+
+    let mut board = create_world_with_registry(Arc::new(create_game_component_registry()));
+    let board_cells = create_cell_grid(&mut board, size, size);
+    let mut dense = bit_grid(Topology::Plane { width: size as i64, height: size as i64 }, conway_rule(), Neighbourhood::Moore).unwrap();
+    for (index, cell) in board_cells.iter().enumerate() {
+        if (index as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) >> 62 == 0 {
+            set_cell_alive(&mut board, *cell, true);
+            set_bit_grid_cell(&mut dense, ((index % size) as i64, (index / size) as i64), true);
+        }
+    }
+    let step_seconds = (0..STEP_REPEATS)
+        .map(|_| {
+            let start = Instant::now();
+            step_life(&ThreadContext::default(), &mut board);
+            start.elapsed().as_secs_f64()
+        })
+        .sum::<f64>() / STEP_REPEATS as f64;
+
+    let dense_step_seconds = (0..STEP_REPEATS)
+        .map(|_| {
+            let start = Instant::now();
+            step_bit_grid(&mut dense);
+            start.elapsed().as_secs_f64()
+        })
+        .sum::<f64>() / STEP_REPEATS as f64;
+
+    drop(board);
+
+    let mut ash = create_world_with_registry(Arc::new(create_game_component_registry()));
+    for y in (0..size as i64).step_by(4) {
+        for x in (0..size as i64).step_by(4) {
+            for coordinate in [(x, y), (x + 1, y), (x, y + 1), (x + 1, y + 1)] {
+                spawn_cell(&mut ash, coordinate, true);
+            }
+        }
+    }
+    maintain_frontier(&mut ash);
+    for _ in 0..GENERATIONS_BEFORE_SLEEP {
+        step_life(&ThreadContext::default(), &mut ash);
+    }
+    let ash_step_seconds = (0..STEP_REPEATS)
+        .map(|_| {
+            let start = Instant::now();
+            step_life(&ThreadContext::default(), &mut ash);
+            maintain_frontier(&mut ash);
+            start.elapsed().as_secs_f64()
+        })
+        .sum::<f64>() / STEP_REPEATS as f64;
+
+    BenchResult { size, cells, spawn_seconds, migrate_seconds, query_seconds, step_seconds, dense_step_seconds, ash_step_seconds }
+}
+
+fn nanoseconds_per_cell(seconds: f64, cells: usize) -> f64 {
+    seconds * 1e9 / cells as f64
+}
+
+fn format_csv(results: &[BenchResult]) -> String {
+    let mut csv = String::from("chunk_size,size,cells,spawn_seconds,migrate_seconds,query_seconds,step_seconds,dense_step_seconds,ash_step_seconds\n");
+    for result in results {
+        csv.push_str(&format!(
+            "{},{},{},{:.6},{:.6},{:.6},{:.6},{:.6},{:.6}\n",
+            chunk_size(),
+            result.size,
+            result.cells,
+            result.spawn_seconds,
+            result.migrate_seconds,
+            result.query_seconds,
+            result.step_seconds,
+            result.dense_step_seconds,
+            result.ash_step_seconds
+        ));
+    }
+    csv
+}
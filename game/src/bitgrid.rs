@@ -0,0 +1,221 @@
+use crate::{GridCoordinate, Neighbourhood, Rule, Topology, resolve_coordinate, rule_births, rule_survives, topology_size, MAX_NEIGHBOUR_COUNT};
+
+const WORD_BITS: usize = 64;
+
+#[derive(Debug, Clone)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    cells: Vec<u64>,
+    next: Vec<u64>,
+    padded_words_per_row: usize,
+    padded: Vec<u64>,
+    rule: Rule,
+    topology: Topology,
+    neighbourhood: Neighbourhood,
+    generation: u64,
+}
+
+pub fn bit_grid(topology: Topology, rule: Rule, neighbourhood: Neighbourhood) -> Option<BitGrid> {
+    let (width, height) = topology_size(&topology)?;
+    let (width, height) = (width as usize, height as usize);
+    let words_per_row = width.div_ceil(WORD_BITS);
+    let padded_words_per_row = (width + 2).div_ceil(WORD_BITS);
+    Some(BitGrid {
+        width,
+        height,
+        words_per_row,
+        cells: vec!(0; words_per_row * height),
+        next: vec!(0; words_per_row * height),
+        padded_words_per_row,
+        padded: vec!(0; padded_words_per_row * (height + 2)),
+        rule,
+        topology,
+        neighbourhood,
+        generation: 0,
+    })
+}
+
+pub fn bit_grid_size(grid: &BitGrid) -> (usize, usize) {
+    (grid.width, grid.height)
+}
+
+pub fn bit_grid_generation(grid: &BitGrid) -> u64 {
+    grid.generation
+}
+
+pub fn get_bit_grid_cell(grid: &BitGrid, coordinate: GridCoordinate) -> bool {
+    match resolve_coordinate(&grid.topology, coordinate) {
+        Some((x, y)) => grid.cells[y as usize * grid.words_per_row + x as usize / WORD_BITS] >> (x as usize % WORD_BITS) & 1 != 0,
+        None => false,
+    }
+}
+
+pub fn set_bit_grid_cell(grid: &mut BitGrid, coordinate: GridCoordinate, alive: bool) -> bool {
+    let Some((x, y)) = resolve_coordinate(&grid.topology, coordinate) else {
+        return false;
+    };
+    let word = &mut grid.cells[y as usize * grid.words_per_row + x as usize / WORD_BITS];
+    let bit = 1u64 << (x as usize % WORD_BITS);
+    if alive {
+        *word |= bit;
+    } else {
+        *word &= !bit;
+    }
+    true
+}
+
+pub fn bit_grid_live_cells(grid: &BitGrid) -> Vec<GridCoordinate> {
+    let mut live = Vec::<GridCoordinate>::default();
+    for y in 0..grid.height {
+        for (word_index, word) in grid.cells[y * grid.words_per_row..(y + 1) * grid.words_per_row].iter().enumerate() {
+            let mut bits = *word;
+            while bits != 0 {
+                let x = word_index * WORD_BITS + bits.trailing_zeros() as usize;
+                live.push((x as i64, y as i64));
+                bits &= bits - 1;
+            }
+        }
+    }
+    live
+}
+
+pub fn bit_grid_population(grid: &BitGrid) -> usize {
+    grid.cells.iter().map(|word| word.count_ones() as usize).sum()
+}
+
+pub fn step_bit_grid(grid: &mut BitGrid) {
+    fill_padded_grid(grid);
+    let survival = neighbour_count_masks(|count| rule_survives(&grid.rule, count));
+    let birth = neighbour_count_masks(|count| rule_births(&grid.rule, count));
+    let diagonals = grid.neighbourhood == Neighbourhood::Moore;
+
+    for y in 0..grid.height {
+        let above = padded_row(grid, y);
+        let current = padded_row(grid, y + 1);
+        let below = padded_row(grid, y + 2);
+        let mut row = vec!(0u64; grid.padded_words_per_row);
+        for word in 0..grid.padded_words_per_row {
+            let left = |row: &[u64]| row[word] << 1 | if word > 0 { row[word - 1] >> 63 } else { 0 };
+            let right = |row: &[u64]| row[word] >> 1 | row.get(word + 1).map_or(0, |next| next << 63);
+            let neighbours = [
+                above[word],
+                below[word],
+                left(current),
+                right(current),
+                if diagonals { left(above) } else { 0 },
+                if diagonals { right(above) } else { 0 },
+                if diagonals { left(below) } else { 0 },
+                if diagonals { right(below) } else { 0 },
+            ];
+            let counts = count_neighbour_bits(&neighbours);
+            let alive = current[word];
+            let survives = select_counts(&counts, &survival);
+            let births = select_counts(&counts, &birth);
+            row[word] = (alive & survives) | (!alive & births);
+        }
+        unpad_row(grid, y, &row);
+    }
+
+    std::mem::swap(&mut grid.cells, &mut grid.next);
+    grid.generation += 1;
+}
+
+fn padded_row(grid: &BitGrid, padded_y: usize) -> &[u64] {
+    &grid.padded[padded_y * grid.padded_words_per_row..(padded_y + 1) * grid.padded_words_per_row]
+}
+
+fn fill_padded_grid(grid: &mut BitGrid) {
+    grid.padded.iter_mut().for_each(|word| *word = 0);
+    for y in 0..grid.height {
+        let target = (y + 1) * grid.padded_words_per_row;
+        for word in 0..grid.words_per_row {
+            let value = grid.cells[y * grid.words_per_row + word];
+            grid.padded[target + word] |= value << 1;
+            if word + 1 < grid.padded_words_per_row {
+                grid.padded[target + word + 1] |= value >> 63;
+            }
+        }
+    }
+
+    let (width, height) = (grid.width as i64, grid.height as i64);
+    let halo = (-1..=width)
+        .flat_map(|x| [(x, -1), (x, height)])
+        .chain((0..height).flat_map(|y| [(-1, y), (width, y)]));
+    for (x, y) in halo.collect::<Vec<_>>() {
+        if get_bit_grid_cell(grid, (x, y)) {
+            let (padded_x, padded_y) = ((x + 1) as usize, (y + 1) as usize);
+            grid.padded[padded_y * grid.padded_words_per_row + padded_x / WORD_BITS] |= 1 << (padded_x % WORD_BITS);
+        }
+    }
+}
+
+fn unpad_row(grid: &mut BitGrid, y: usize, row: &[u64]) {
+    for word in 0..grid.words_per_row {
+        let value = row[word] >> 1 | row.get(word + 1).map_or(0, |next| next << 63);
+        let valid_bits = (grid.width - word * WORD_BITS).min(WORD_BITS);
+        let mask = if valid_bits == WORD_BITS { u64::MAX } else { (1 << valid_bits) - 1 };
+        grid.next[y * grid.words_per_row + word] = value & mask;
+    }
+}
+
+fn neighbour_count_masks(selected: impl Fn(usize) -> bool) -> Vec<usize> {
+    (0..=MAX_NEIGHBOUR_COUNT).filter(|count| selected(*count)).collect()
+}
+
+fn count_neighbour_bits(neighbours: &[u64; 8]) -> [u64; 4] {
+    let mut counts = [0u64; 4];
+    for neighbour in neighbours {
+        let mut carry = *neighbour;
+        for bit in &mut counts {
+            let next_carry = *bit & carry;
+            *bit ^= carry;
+            carry = next_carry;
+        }
+    }
+    counts
+}
+
+fn select_counts(counts: &[u64; 4], selected: &[usize]) -> u64 {
+    selected.iter().fold(0, |mask, count| {
+        mask | counts.iter().enumerate().fold(u64::MAX, |matches, (bit, word)| {
+            matches & if count >> bit & 1 != 0 { *word } else { !*word }
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{conway_rule, Twist};
+
+    #[test]
+    fn word_boundaries_count_neighbours() {
+        let mut grid = bit_grid(Topology::Plane { width: 130, height: 3 }, conway_rule(), Neighbourhood::Moore).unwrap();
+        for x in 63..66 {
+            set_bit_grid_cell(&mut grid, (x, 1), true);
+        }
+        step_bit_grid(&mut grid);
+        assert_eq!(bit_grid_live_cells(&grid), vec!((64, 0), (64, 1), (64, 2)));
+        step_bit_grid(&mut grid);
+        assert_eq!(bit_grid_live_cells(&grid), vec!((63, 1), (64, 1), (65, 1)));
+    }
+
+    #[test]
+    fn halo_follows_topology() {
+        let blinker = [(0, 0), (0, 1), (0, 4)];
+        for (topology, expected) in [
+            (Topology::Torus { width: 5, height: 5 }, vec!((0, 0), (1, 0), (4, 0))),
+            (Topology::Plane { width: 5, height: 5 }, vec!()),
+            (Topology::KleinBottle { width: 5, height: 5, twist: Twist::Height }, vec!((0, 0), (1, 0), (4, 4))),
+        ] {
+            let mut grid = bit_grid(topology, conway_rule(), Neighbourhood::Moore).unwrap();
+            for coordinate in blinker {
+                set_bit_grid_cell(&mut grid, coordinate, true);
+            }
+            step_bit_grid(&mut grid);
+            assert_eq!(bit_grid_live_cells(&grid), expected, "{}", topology);
+        }
+    }
+}
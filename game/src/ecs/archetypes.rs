@@ -22,6 +22,7 @@ fn create_archetype_entity_location(chunk: ChunkIndex, component: ComponentIndex
     ArchetypeEntityLocation { chunk, component }
 }
 
+#[derive(Clone)]
 pub struct ArchetypeComponentSearchIndex {
     component_slices: SliceVec<ComponentTypeId>
 }
@@ -33,7 +34,11 @@ fn archetype_component_search_index() -> ArchetypeComponentSearchIndex {
 }
 
 fn push_layout_to_search(search_index: &mut ArchetypeComponentSearchIndex, layout: &EntityLayout) {
-    search_index.component_slices.push(copy_layout_components(layout))
+    search_index.component_slices.push(copy_layout_components(layout).chain(layout_shared_component_types(layout)))
+}
+
+pub fn search_index_iter(search_index: &ArchetypeComponentSearchIndex) -> impl Iterator<Item = &[ComponentTypeId]> + '_ {
+    search_index.component_slices.iter_from(0)
 }
 
 pub fn search_archetypes_for<'a, F: LayoutFilter + 'a>(
@@ -70,6 +75,10 @@ pub fn get_or_create_archetype_mut<'a>(archetypes: &'a mut Archetypes, layout: &
 
 pub fn create_archetype_if_non_existant(archetypes: &mut Archetypes, layout: &EntityLayout) {
     if !contains_archetype(archetypes, &layout) {
+        if let Some(index) = find_reusable_archetype_index(archetypes, layout) {
+            archetypes.inner[index.0].layout = layout.clone();
+            return;
+        }
         let archetype = create_archetype(layout.clone(), get_next_index(archetypes));
         push_layout_to_search(&mut archetypes.search_index, &archetype.layout);
         append_archetype(archetypes, archetype);
@@ -77,7 +86,22 @@ pub fn create_archetype_if_non_existant(archetypes: &mut Archetypes, layout: &En
 }
 
 fn contains_archetype(archetypes: &Archetypes, layout: &EntityLayout) -> bool {
-    search_archetypes_for(&archetypes.search_index, layout.clone(), 0).next().is_some()
+    find_archetype_index_for_layout(archetypes, layout).is_some()
+}
+
+fn find_archetype_index_for_layout(archetypes: &Archetypes, layout: &EntityLayout) -> Option<ArchetypeIndex> {
+    search_archetypes_for(&archetypes.search_index, layout.clone(), 0)
+        .find(|index| layout_shared_values_match(&archetypes.inner[index.0].layout, layout))
+}
+
+// An empty archetype whose shared values differ from the layout's can take the layout over,
+// so shared values that come and go (tiles, say) don't leave a trail of dead archetypes.
+fn find_reusable_archetype_index(archetypes: &Archetypes, layout: &EntityLayout) -> Option<ArchetypeIndex> {
+    if layout.shared.is_empty() {
+        return None;
+    }
+    search_archetypes_for(&archetypes.search_index, layout.clone(), 0)
+        .find(|index| chunk_iter(&archetypes.inner[index.0].chunks).next().is_none())
 }
 
 fn get_next_index(archetypes: &Archetypes) -> ArchetypeIndex {
@@ -89,14 +113,14 @@ fn append_archetype(archetypes: &mut Archetypes, archetype: Archetype) {
 }
 
 fn get_archetype_for_layout_mut<'a>(archetypes: &'a mut Archetypes, layout: &EntityLayout) -> Option<&'a mut Archetype> {
-    if let Some(index) = search_archetypes_for(&archetypes.search_index, layout.clone(), 0).next() {
+    if let Some(index) = find_archetype_index_for_layout(archetypes, layout) {
         return Some(&mut archetypes.inner[index.0]);
     }
     None
 }
 
 pub fn get_archetype_for_layout<'a>(archetypes: &'a Archetypes, layout: &EntityLayout) -> Option<&'a Archetype> {
-    if let Some(index) = search_archetypes_for(&archetypes.search_index, layout.clone(), 0).next() {
+    if let Some(index) = find_archetype_index_for_layout(archetypes, layout) {
         return Some(&archetypes.inner[index.0]);
     }
     None
@@ -106,6 +130,10 @@ pub fn get_archetype_for_entity_location(archetypes: &Archetypes, location: Enti
     get_archetype(archetypes, location.archetype_index)
 }
 
+pub fn get_archetype_for_entity_location_mut(archetypes: &mut Archetypes, location: EntityLocation) -> &mut Archetype {
+    get_archetype_mut(archetypes, location.archetype_index)
+}
+
 pub fn get_archetype(archetypes: &Archetypes, index: ArchetypeIndex) -> &Archetype {
     &archetypes.inner[index.0]
 }
@@ -114,6 +142,14 @@ fn get_archetype_mut(archetypes: &mut Archetypes, index: ArchetypeIndex) -> &mut
     &mut archetypes.inner[index.0]
 }
 
+pub fn archetype_iter(archetypes: &Archetypes) -> std::slice::Iter<'_, Archetype> {
+    archetypes.inner.iter()
+}
+
+pub fn get_chunk_pool(archetypes: &Archetypes) -> &ComponentChunkPool {
+    &archetypes.chunk_pool
+}
+
 pub struct ArchetypeIterator<'a> {
     indicies: Box<dyn Iterator<Item = ArchetypeIndex> + 'a>,
     world: &'a World,
@@ -173,7 +209,7 @@ fn increment_current_component_index(archetypes: &mut Archetypes, layout: &Entit
 fn add_new_chunk(archetypes: &mut Archetypes, layout: &EntityLayout) {
     let archetype = get_archetype_for_layout(archetypes, &layout).unwrap();
     let index = next_chunk_index(&archetype.chunks);
-    let chunk = component_chunk(&mut archetypes.chunk_pool, index, deduce_chunk_layout(layout));
+    let chunk = component_chunk(&mut archetypes.chunk_pool, index, deduce_chunk_layout(layout), layout.shared.clone());
     let archetype = get_archetype_for_layout_mut(archetypes, &layout).unwrap();
     add_chunk(&mut archetype.chunks, chunk);
 }
@@ -189,4 +225,44 @@ pub fn move_to_previous_archetype_entity_location(archetypes: &mut Archetypes, a
     }
     let chunk = remove_last_chunk(chunks).unwrap();
     recycle_chunk(&mut archetypes.chunk_pool, chunk);
-}
\ No newline at end of file
+}
+
+pub fn archetype_entity_count(archetype: &Archetype) -> usize {
+    chunk_iter(&archetype.chunks).map(chunk_entity_count).sum()
+}
+
+pub fn archetype_count(archetypes: &Archetypes) -> usize {
+    archetypes.inner.len()
+}
+
+pub fn sort_archetype_chunks(
+    archetypes: &Archetypes,
+    index: ArchetypeIndex,
+    compare: impl FnMut((&ComponentChunk, ComponentIndex), (&ComponentChunk, ComponentIndex)) -> std::cmp::Ordering,
+    row_moved: impl FnMut(Entity, ArchetypeEntityLocation)
+) -> usize {
+    sort_chunks(&archetypes.inner[index.0].chunks, compare, row_moved)
+}
+
+pub fn shrink_archetype_chunks(archetypes: &mut Archetypes, index: ArchetypeIndex) {
+    shrink_chunks(&mut get_archetype_mut(archetypes, index).chunks);
+}
+
+pub fn trim_archetypes_chunk_pool(archetypes: &mut Archetypes, limit: usize) -> usize {
+    trim_chunk_pool(&mut archetypes.chunk_pool, limit)
+}
+
+pub fn clone_archetypes(archetypes: &Archetypes, mut clone_stream: impl FnMut(ComponentTypeId, &[u8], &mut [u8])) -> Archetypes {
+    Archetypes {
+        inner: archetypes.inner
+            .iter()
+            .map(|archetype| Archetype {
+                layout: archetype.layout.clone(),
+                index: archetype.index,
+                chunks: clone_component_chunks(&archetype.chunks, &mut clone_stream)
+            })
+            .collect(),
+        chunk_pool: ComponentChunkPool::default(),
+        search_index: archetypes.search_index.clone()
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    collections::*,
+    fmt
+};
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloneWorldError {
+    ComponentNotRegistered(&'static str),
+    ComponentNotCloneable(&'static str),
+}
+
+impl fmt::Display for CloneWorldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CloneWorldError::ComponentNotRegistered(name) => write!(f, "component '{}' is not registered", name),
+            CloneWorldError::ComponentNotCloneable(key) => write!(f, "component '{}' is not registered as Copy or Clone", key),
+        }
+    }
+}
+
+pub fn try_clone_world(world: &World) -> Result<World, CloneWorldError> {
+    for archetype in archetype_iter(&world.archetypes) {
+        for component_type_id in &archetype.layout.components {
+            let registration = get_component_registration(&world.registry, *component_type_id)
+                .ok_or_else(|| CloneWorldError::ComponentNotRegistered(component_type_name(*component_type_id)))?;
+            if !is_registered_component_cloneable(registration) {
+                return Err(CloneWorldError::ComponentNotCloneable(registration.key));
+            }
+        }
+    }
+
+    let registry = &world.registry;
+    let mut cloned = World {
+        entities: world.entities.clone(),
+        archetypes: clone_archetypes(&world.archetypes, |component_type_id, source, target| {
+            let registration = get_component_registration(registry, component_type_id).unwrap();
+            clone_registered_component_stream(registration, source, target);
+        }),
+        component_storages: clone_component_storages(&world.component_storages),
+        resources: resources(),
+        registry: registry.clone(),
+    };
+
+    for registration in resource_registration_iter(registry) {
+        clone_registered_resource(registration, world, &mut cloned);
+    }
+    Ok(cloned)
+}
+
+pub fn clone_world(world: &World) -> World {
+    try_clone_world(world).unwrap_or_else(|error| panic!("{}", error))
+}
+
+pub struct WorldHistory {
+    capacity: usize,
+    worlds: VecDeque<World>
+}
+
+pub fn world_history(capacity: usize) -> WorldHistory {
+    WorldHistory {
+        capacity,
+        worlds: VecDeque::with_capacity(capacity)
+    }
+}
+
+pub fn push_world_history(history: &mut WorldHistory, world: &World) {
+    if history.capacity == 0 {
+        return;
+    }
+    if history.worlds.len() == history.capacity {
+        history.worlds.pop_front();
+    }
+    history.worlds.push_back(clone_world(world));
+}
+
+pub fn step_back_world_history(history: &mut WorldHistory) -> Option<World> {
+    history.worlds.pop_back()
+}
+
+pub fn world_history_len(history: &WorldHistory) -> usize {
+    history.worlds.len()
+}
+
+pub fn clear_world_history(history: &mut WorldHistory) {
+    history.worlds.clear();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(u32, u32);
+    #[derive(Debug, Clone, PartialEq)]
+    struct Name(String);
+    #[derive(Debug, Clone, PartialEq)]
+    struct Generation(u64);
+
+    fn create_test_world() -> World {
+        let mut registry = component_registry();
+        register_component::<Position>(&mut registry, "Position");
+        register_component_copy::<Position>(&mut registry);
+        register_component::<Name>(&mut registry, "Name");
+        register_component_clone::<Name>(&mut registry);
+        register_resource::<Generation>(&mut registry, "Generation", |generation, writer| writer.write_u64(generation.0), |reader| Ok(Generation(reader.read_u64()?)));
+        register_resource_clone::<Generation>(&mut registry);
+        create_world_with_registry(Arc::new(registry))
+    }
+
+    #[test]
+    fn cloned_world_is_independent() {
+        let mut world = create_test_world();
+        let entities: Vec<Entity> = (0..1000)
+            .map(|index| {
+                let entity = add_entity(&mut world);
+                add_component(&mut world, entity, Position(index, index * 2));
+                if index.is_multiple_of(3) {
+                    add_component(&mut world, entity, Name(format!("cell {}", index)));
+                }
+                entity
+            })
+            .collect();
+        add_resource(&mut world, Generation(7));
+
+        let cloned = clone_world(&world);
+        get_component_mut::<Position>(&mut world, entities[0]).unwrap().0 = 99;
+        get_component_mut::<Name>(&mut world, entities[3]).unwrap().0.push_str(" changed");
+        get_resource_mut::<Generation>(&mut world).unwrap().0 += 1;
+        drop(world);
+
+        for (index, entity) in entities.iter().enumerate() {
+            let index = index as u32;
+            assert_eq!(get_component::<Position>(&cloned, *entity), Some(&Position(index, index * 2)));
+            let name = get_component::<Name>(&cloned, *entity);
+            if index.is_multiple_of(3) {
+                assert_eq!(name, Some(&Name(format!("cell {}", index))));
+            } else {
+                assert_eq!(name, None);
+            }
+        }
+        assert_eq!(get_resource::<Generation>(&cloned), Some(&Generation(7)));
+    }
+
+    #[test]
+    fn history_keeps_most_recent_worlds() {
+        let mut world = create_test_world();
+        let entity = add_entity(&mut world);
+        add_component(&mut world, entity, Position(0, 0));
+
+        let mut history = world_history(2);
+        for generation in 1..=3 {
+            push_world_history(&mut history, &world);
+            get_component_mut::<Position>(&mut world, entity).unwrap().0 = generation;
+        }
+        assert_eq!(world_history_len(&history), 2);
+
+        let previous = step_back_world_history(&mut history).unwrap();
+        assert_eq!(get_component::<Position>(&previous, entity), Some(&Position(2, 0)));
+        let previous = step_back_world_history(&mut history).unwrap();
+        assert_eq!(get_component::<Position>(&previous, entity), Some(&Position(1, 0)));
+        assert!(step_back_world_history(&mut history).is_none());
+    }
+
+    #[test]
+    fn unregistered_components_are_reported() {
+        let mut world = create_world();
+        let entity = add_entity(&mut world);
+        add_component(&mut world, entity, Position(0, 0));
+        assert_eq!(try_clone_world(&world).err(), Some(CloneWorldError::ComponentNotRegistered(component_type_name(component_type_of::<Position>()))));
+    }
+}
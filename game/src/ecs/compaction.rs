@@ -0,0 +1,72 @@
+use super::*;
+
+pub const DEFAULT_CHUNK_POOL_LIMIT: usize = 4;
+
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct CompactionStats {
+    pub chunks_freed: usize,
+}
+
+// Rows are only swap-removed from an archetype's head chunk and emptied chunks go straight
+// to the pool, so chunks never need rows moved between them; only spare capacity is left.
+pub fn compact_archetype(world: &mut World, archetype_index: ArchetypeIndex) {
+    shrink_archetype_chunks(&mut world.archetypes, archetype_index);
+}
+
+pub fn compact_world(world: &mut World, chunk_pool_limit: usize) -> CompactionStats {
+    let archetype_indicies: Vec<ArchetypeIndex> = archetype_iter(&world.archetypes)
+        .map(|archetype| archetype.index)
+        .collect();
+
+    for archetype_index in archetype_indicies {
+        compact_archetype(world, archetype_index);
+    }
+
+    CompactionStats {
+        chunks_freed: trim_archetypes_chunk_pool(&mut world.archetypes, chunk_pool_limit),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(usize);
+    #[derive(Debug, PartialEq)]
+    struct Alive(bool);
+
+    fn spawn_positions(world: &mut World, count: usize) -> Vec<Entity> {
+        (0..count)
+            .map(|index| {
+                let entity = add_entity(world);
+                add_component(world, entity, Position(index));
+                entity
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compacting_world_trims_chunk_pool() {
+        let mut world = create_world();
+        let count = 2000;
+        let entities = spawn_positions(&mut world, count);
+        assert!(world_stats(&world).chunk_count > 1);
+
+        for entity in &entities {
+            add_component(&mut world, *entity, Alive(true));
+        }
+        let recycled_chunk_count = world_stats(&world).chunk_pool.recycled_chunk_count;
+        assert!(recycled_chunk_count > 0);
+
+        let stats = compact_world(&mut world, 0);
+        let world_stats = world_stats(&world);
+        assert_eq!(stats.chunks_freed, recycled_chunk_count);
+        assert_eq!(world_stats.chunk_pool.recycled_chunk_count, 0);
+        assert_eq!(world_stats.entity_count, count);
+
+        for (index, entity) in entities.iter().enumerate() {
+            assert_eq!(get_component::<Position>(&world, *entity), Some(&Position(index)));
+        }
+    }
+}
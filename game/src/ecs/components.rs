@@ -6,6 +6,7 @@ use std::{
     mem::*,
     marker::*,
     collections::*,
+    cmp::Ordering,
 };
 
 use gol_engine::gol_assert;
@@ -15,9 +16,21 @@ use super::*;
 #[derive(Copy, Clone)]
 pub struct ChunkIndex(usize);
 
+impl From<ChunkIndex> for usize {
+    fn from(from: ChunkIndex) -> Self {
+        from.0
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub struct ComponentIndex(usize);
 
+impl From<ComponentIndex> for usize {
+    fn from(from: ComponentIndex) -> Self {
+        from.0
+    }
+}
+
 impl ComponentIndex {
     pub fn increment(&mut self) {
         self.0 += 1;
@@ -33,8 +46,9 @@ impl ComponentIndex {
 }
 
 const CHUNK_SIZE: usize = 16384;
-const CHUNK_ALIGN: usize = 1;
+const CHUNK_ALIGN: usize = 64;
 
+#[derive(Clone)]
 struct ComponentChunkLayoutStream {
     component_type_id: ComponentTypeId,
     offset: usize,
@@ -53,8 +67,11 @@ fn deduce_component_chunk_layout_stream(
     offset: &mut usize,
     max_slots: usize,
     component_type_id: ComponentTypeId,
-    stride: usize
+    stride: usize,
+    align: usize
  ) -> ComponentChunkLayoutStream {
+    gol_assert!(align <= CHUNK_ALIGN);
+    *offset = align_offset(*offset, align);
     let width = stride * max_slots;
     let stream = component_chunk_layout_stream(component_type_id, *offset, stride);
     *offset += width;
@@ -65,6 +82,7 @@ fn get_offset_in_chunk_stream(stream: &ComponentChunkLayoutStream, index: Compon
     stream.offset + (index.0 * stream.stride)
 }
 
+#[derive(Clone)]
 pub struct ComponentChunkLayout {
     max_slots: usize,
     streams: Vec<ComponentChunkLayoutStream>
@@ -74,25 +92,36 @@ fn component_chunk_layout(max_slots: usize, streams: Vec<ComponentChunkLayoutStr
     ComponentChunkLayout { max_slots, streams }
 }
 
+fn align_offset(offset: usize, align: usize) -> usize {
+    offset.next_multiple_of(align)
+}
+
 pub fn deduce_chunk_layout(layout: &EntityLayout) -> ComponentChunkLayout {
-    let max_slots = CHUNK_SIZE / get_layout_size(layout);
-    component_chunk_layout(max_slots, deduce_component_chunk_layout_streams(layout, max_slots))
+    let mut max_slots = CHUNK_SIZE / (get_layout_size(layout) + size_of::<Entity>());
+    loop {
+        let (streams, end) = deduce_component_chunk_layout_streams(layout, max_slots);
+        if end <= CHUNK_SIZE {
+            return component_chunk_layout(max_slots, streams);
+        }
+        max_slots -= 1;
+    }
 }
 
-fn deduce_component_chunk_layout_streams(layout: &EntityLayout, max_slots: usize) -> Vec<ComponentChunkLayoutStream> {
-    let mut offset = 0;
+fn deduce_component_chunk_layout_streams(layout: &EntityLayout, max_slots: usize) -> (Vec<ComponentChunkLayoutStream>, usize) {
+    let mut offset = size_of::<Entity>() * max_slots;
     let mut streams = Vec::<ComponentChunkLayoutStream>::default();
 
-    for (component_type_id, component_size) in component_type_and_size_iter(layout) {
+    for ((component_type_id, component_size), component_align) in component_type_and_size_iter(layout).zip(&layout.component_aligns) {
         let component_stream = deduce_component_chunk_layout_stream(
             &mut offset,
             max_slots,
             *component_type_id,
-            *component_size);
+            *component_size,
+            *component_align);
         streams.push(component_stream);
     }
 
-    streams
+    (streams, offset)
 }
 
 fn get_chunk_stream_for_component(component_type_id: ComponentTypeId, chunk_layout: &ComponentChunkLayout) -> &ComponentChunkLayoutStream {
@@ -103,6 +132,19 @@ fn get_chunk_stream_for_component(component_type_id: ComponentTypeId, chunk_layo
         .unwrap()
 }
 
+fn find_chunk_stream_for_component(component_type_id: ComponentTypeId, chunk_layout: &ComponentChunkLayout) -> Option<&ComponentChunkLayoutStream> {
+    chunk_layout.streams
+        .iter()
+        .rev()
+        .find(|stream| stream.component_type_id == component_type_id)
+}
+
+pub fn get_component_bytes_in_chunk(chunk: &ComponentChunk, component_type_id: ComponentTypeId, index: ComponentIndex) -> Option<&[u8]> {
+    let stream = find_chunk_stream_for_component(component_type_id, &chunk.chunk_layout)?;
+    let offset = get_offset_in_chunk_stream(stream, index);
+    Some(unsafe { from_raw_parts(chunk.storage.add(offset), stream.stride) })
+}
+
 fn get_chunk_stream_offset_for_component(component_type: ComponentTypeId, chunk_layout: &ComponentChunkLayout, index: ComponentIndex) -> usize {
     let stream = get_chunk_stream_for_component(component_type, chunk_layout);
     get_offset_in_chunk_stream(stream, index)
@@ -112,14 +154,26 @@ pub struct ComponentChunk {
     storage: *mut u8,
     pub chunk_index: ChunkIndex,
     pub current_component_index: ComponentIndex,
-    chunk_layout: ComponentChunkLayout
+    chunk_layout: ComponentChunkLayout,
+    shared: Vec<SharedComponentValue>
+}
+
+// Chunk memory is only written while the owning World is borrowed mutably, so
+// readers sharing a &World never race with a writer. Components are Sync.
+unsafe impl Sync for ComponentChunk {}
+
+impl Drop for ComponentChunk {
+    fn drop(&mut self) {
+        free_chunk_storage(self.storage);
+    }
 }
 
-pub fn component_chunk(chunk_pool: &mut ComponentChunkPool, chunk_index: ChunkIndex, chunk_layout: ComponentChunkLayout) -> ComponentChunk {
+pub fn component_chunk(chunk_pool: &mut ComponentChunkPool, chunk_index: ChunkIndex, chunk_layout: ComponentChunkLayout, shared: Vec<SharedComponentValue>) -> ComponentChunk {
     if let Some(mut chunk) = chunk_pool.recycled.pop() {
         chunk.chunk_index = chunk_index;
         chunk.chunk_layout = chunk_layout;
         chunk.current_component_index = ComponentIndex(0);
+        chunk.shared = shared;
         return chunk
     }
 
@@ -127,14 +181,42 @@ pub fn component_chunk(chunk_pool: &mut ComponentChunkPool, chunk_index: ChunkIn
         storage: allocate_chunk_storage(),
         chunk_index,
         current_component_index: ComponentIndex(0),
-        chunk_layout
+        chunk_layout,
+        shared
     }
 }
 
-fn is_chunk_full(chunk: &ComponentChunk) -> bool {
+pub fn is_chunk_full(chunk: &ComponentChunk) -> bool {
     chunk.current_component_index.0 == chunk.chunk_layout.max_slots - 1
 }
 
+pub fn chunk_entity_count(chunk: &ComponentChunk) -> usize {
+    chunk.current_component_index.0 + 1
+}
+
+pub fn chunk_component_indicies(chunk: &ComponentChunk) -> impl Iterator<Item = ComponentIndex> {
+    (0..chunk_entity_count(chunk)).map(ComponentIndex)
+}
+
+pub fn get_chunk_shared_value(chunk: &ComponentChunk, component_type_id: ComponentTypeId) -> Option<&SharedComponentValue> {
+    chunk.shared
+        .iter()
+        .find(|value| value.component_type_id == component_type_id)
+}
+
+pub fn chunk_max_slots(chunk: &ComponentChunk) -> usize {
+    chunk.chunk_layout.max_slots
+}
+
+pub fn chunk_bytes_used(chunk: &ComponentChunk) -> usize {
+    let row_size: usize = chunk.chunk_layout.streams.iter().map(|stream| stream.stride).sum();
+    row_size * chunk_entity_count(chunk)
+}
+
+pub fn chunk_size() -> usize {
+    CHUNK_SIZE
+}
+
 pub fn store_component_at_location<C>(chunks: &ComponentChunks, location: ArchetypeEntityLocation, component: C)
 where C: Component {
     let component_type_id = component_type_of::<C>(); 
@@ -143,10 +225,85 @@ where C: Component {
     store_component_in_chunk_at_offset(chunk, offset, component);
 }
 
+fn get_entity_offset_in_chunk(index: ComponentIndex) -> usize {
+    index.0 * size_of::<Entity>()
+}
+
+pub fn get_entity_in_chunk(chunk: &ComponentChunk, index: ComponentIndex) -> Entity {
+    unsafe {
+        *(chunk.storage.add(get_entity_offset_in_chunk(index)) as *const Entity)
+    }
+}
+
+fn set_entity_in_chunk(chunk: &ComponentChunk, index: ComponentIndex, entity: Entity) {
+    unsafe {
+        *(chunk.storage.add(get_entity_offset_in_chunk(index)) as *mut Entity) = entity;
+    }
+}
+
+pub fn store_entity_at_location(chunks: &ComponentChunks, location: ArchetypeEntityLocation, entity: Entity) {
+    set_entity_in_chunk(get_chunk(chunks, location.chunk), location.component, entity);
+}
+
+pub fn swap_remove_entity_at_location(chunks: &ComponentChunks, location: ArchetypeEntityLocation) -> Option<Entity> {
+    let chunk = get_chunk(chunks, location.chunk);
+    let head_chunk = get_chunk(chunks, current_chunk_index(chunks));
+    if chunk.chunk_index.0 == head_chunk.chunk_index.0 && location.component == head_chunk.current_component_index {
+        return None;
+    }
+    let moved = get_entity_in_chunk(head_chunk, head_chunk.current_component_index);
+    set_entity_in_chunk(chunk, location.component, moved);
+    Some(moved)
+}
+
+pub fn copy_row_between_chunks(source_chunk: &ComponentChunk, source_index: ComponentIndex, target_chunk: &ComponentChunk, target_index: ComponentIndex) {
+    set_entity_in_chunk(target_chunk, target_index, get_entity_in_chunk(source_chunk, source_index));
+    for stream in &source_chunk.chunk_layout.streams {
+        let target_stream = match find_chunk_stream_for_component(stream.component_type_id, &target_chunk.chunk_layout) {
+            Some(target_stream) => target_stream,
+            None => continue
+        };
+        let source_offset = get_offset_in_chunk_stream(stream, source_index);
+        let target_offset = get_offset_in_chunk_stream(target_stream, target_index);
+        unsafe {
+            std::ptr::copy_nonoverlapping(source_chunk.storage.add(source_offset), target_chunk.storage.add(target_offset), stream.stride);
+        }
+    }
+}
+
+pub fn clone_row_between_chunks(
+    source_chunk: &ComponentChunk,
+    source_index: ComponentIndex,
+    target_chunk: &ComponentChunk,
+    target_index: ComponentIndex,
+    mut clone_stream: impl FnMut(ComponentTypeId, &[u8], &mut [u8])
+) {
+    set_entity_in_chunk(target_chunk, target_index, get_entity_in_chunk(source_chunk, source_index));
+    for stream in &source_chunk.chunk_layout.streams {
+        let source_offset = get_offset_in_chunk_stream(stream, source_index);
+        let target_offset = get_chunk_stream_offset_for_component(stream.component_type_id, &target_chunk.chunk_layout, target_index);
+        let (source, target) = unsafe { (
+            from_raw_parts(source_chunk.storage.add(source_offset), stream.stride),
+            from_raw_parts_mut(target_chunk.storage.add(target_offset), stream.stride)
+        ) };
+        clone_stream(stream.component_type_id, source, target);
+    }
+}
+
+pub fn swap_remove_row_at_location(chunks: &ComponentChunks, location: ArchetypeEntityLocation) -> Option<Entity> {
+    let chunk = get_chunk(chunks, location.chunk);
+    let head_chunk = get_chunk(chunks, current_chunk_index(chunks));
+    if chunk.chunk_index.0 == head_chunk.chunk_index.0 && location.component == head_chunk.current_component_index {
+        return None;
+    }
+    copy_row_between_chunks(head_chunk, head_chunk.current_component_index, chunk, location.component);
+    Some(get_entity_in_chunk(chunk, location.component))
+}
+
 fn store_component_in_chunk_at_offset<C>(chunk: &ComponentChunk, offset: usize, component: C) where C:Component {
     unsafe {
         let storage_location = chunk.storage.add(offset) as *mut C;
-        *storage_location = component;
+        std::ptr::write_unaligned(storage_location, component);
     }
 }
 
@@ -156,11 +313,20 @@ fn allocate_chunk_storage() -> *mut u8 {
     storage
 }
 
+fn free_chunk_storage(storage: *mut u8) {
+    let layout = Layout::from_size_align(CHUNK_SIZE, CHUNK_ALIGN).unwrap();
+    unsafe { dealloc(storage, layout) };
+}
+
 #[derive(Default)]
 pub struct ComponentChunkPool {
     recycled: Vec<ComponentChunk>
 }
 
+pub fn recycled_chunk_count(chunk_pool: &ComponentChunkPool) -> usize {
+    chunk_pool.recycled.len()
+}
+
 #[derive(Default)]
 pub struct ComponentChunks {
     inner: Vec<ComponentChunk>
@@ -175,12 +341,13 @@ impl<'a> Iterator for ArchetypeComponentIterator<'a> {
     type Item = (&'a ComponentChunk, ComponentIndex);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(mut value) = self.current {
+        if let Some(value) = self.current {
             if value.1 == value.0.current_component_index { 
                 self.current = None;
             } else {
-                value.1.increment();
-                self.current = Some((value.0, value.1));
+                let mut next = value.1;
+                next.increment();
+                self.current = Some((value.0, next));
             }
             return Some(value);
         }
@@ -199,6 +366,10 @@ impl ComponentChunks {
     }
 }
 
+pub fn chunk_iter(chunks: &ComponentChunks) -> Iter<'_, ComponentChunk> {
+    chunks.inner.iter()
+}
+
 pub fn create_component_chunks() -> ComponentChunks {
     ComponentChunks::default()
 }
@@ -207,10 +378,105 @@ pub fn recycle_chunk(chunk_pool: &mut ComponentChunkPool, chunk: ComponentChunk)
     chunk_pool.recycled.push(chunk);
 }
 
+pub fn sort_chunks(
+    chunks: &ComponentChunks,
+    mut compare: impl FnMut((&ComponentChunk, ComponentIndex), (&ComponentChunk, ComponentIndex)) -> Ordering,
+    mut row_moved: impl FnMut(Entity, ArchetypeEntityLocation)
+) -> usize {
+    let rows: Vec<ArchetypeEntityLocation> = chunks.inner
+        .iter()
+        .flat_map(|chunk| chunk_component_indicies(chunk).map(move |component| ArchetypeEntityLocation { chunk: chunk.chunk_index, component }))
+        .collect();
+    let mut order: Vec<usize> = (0..rows.len()).collect();
+    order.sort_by(|a, b| {
+        let (a, b) = (rows[*a], rows[*b]);
+        compare((get_chunk(chunks, a.chunk), a.component), (get_chunk(chunks, b.chunk), b.component))
+    });
+
+    let mut position_of_row: Vec<usize> = (0..rows.len()).collect();
+    let mut row_at_position: Vec<usize> = (0..rows.len()).collect();
+    let mut rows_moved = 0;
+    for (position, row) in order.into_iter().enumerate() {
+        let current_position = position_of_row[row];
+        if current_position == position {
+            continue;
+        }
+
+        let (target, source) = (rows[position], rows[current_position]);
+        swap_rows_between_chunks(get_chunk(chunks, target.chunk), target.component, get_chunk(chunks, source.chunk), source.component);
+        let displaced_row = row_at_position[position];
+        row_at_position[current_position] = displaced_row;
+        position_of_row[displaced_row] = current_position;
+        row_at_position[position] = row;
+        position_of_row[row] = position;
+
+        row_moved(get_entity_in_chunk(get_chunk(chunks, target.chunk), target.component), target);
+        row_moved(get_entity_in_chunk(get_chunk(chunks, source.chunk), source.component), source);
+        rows_moved += 1;
+    }
+    rows_moved
+}
+
+fn swap_rows_between_chunks(chunk: &ComponentChunk, index: ComponentIndex, other_chunk: &ComponentChunk, other_index: ComponentIndex) {
+    let entity = get_entity_in_chunk(chunk, index);
+    set_entity_in_chunk(chunk, index, get_entity_in_chunk(other_chunk, other_index));
+    set_entity_in_chunk(other_chunk, other_index, entity);
+    for stream in &chunk.chunk_layout.streams {
+        let other_stream = get_chunk_stream_for_component(stream.component_type_id, &other_chunk.chunk_layout);
+        unsafe {
+            std::ptr::swap_nonoverlapping(
+                chunk.storage.add(get_offset_in_chunk_stream(stream, index)),
+                other_chunk.storage.add(get_offset_in_chunk_stream(other_stream, other_index)),
+                stream.stride
+            );
+        }
+    }
+}
+
+pub fn trim_chunk_pool(chunk_pool: &mut ComponentChunkPool, limit: usize) -> usize {
+    let mut freed = 0;
+    while chunk_pool.recycled.len() > limit {
+        chunk_pool.recycled.pop();
+        freed += 1;
+    }
+    freed
+}
+
+pub fn clone_component_chunks(chunks: &ComponentChunks, mut clone_stream: impl FnMut(ComponentTypeId, &[u8], &mut [u8])) -> ComponentChunks {
+    let mut cloned = create_component_chunks();
+    for source_chunk in &chunks.inner {
+        let target_chunk = ComponentChunk {
+            storage: allocate_chunk_storage(),
+            chunk_index: source_chunk.chunk_index,
+            current_component_index: source_chunk.current_component_index,
+            chunk_layout: source_chunk.chunk_layout.clone(),
+            shared: source_chunk.shared.clone()
+        };
+        let count = chunk_entity_count(source_chunk);
+        unsafe {
+            std::ptr::copy_nonoverlapping(source_chunk.storage, target_chunk.storage, count * size_of::<Entity>());
+        }
+        for stream in &source_chunk.chunk_layout.streams {
+            let width = stream.stride * count;
+            let (source, target) = unsafe { (
+                from_raw_parts(source_chunk.storage.add(stream.offset), width),
+                from_raw_parts_mut(target_chunk.storage.add(stream.offset), width)
+            ) };
+            clone_stream(stream.component_type_id, source, target);
+        }
+        add_chunk(&mut cloned, target_chunk);
+    }
+    cloned
+}
+
 pub fn add_chunk(chunks: &mut ComponentChunks, chunk: ComponentChunk) {
     chunks.inner.push(chunk)
 }
 
+pub fn shrink_chunks(chunks: &mut ComponentChunks) {
+    chunks.inner.shrink_to_fit();
+}
+
 pub fn remove_last_chunk(chunks: &mut ComponentChunks) -> Option<ComponentChunk> {
     chunks.inner.pop()
 }
@@ -241,22 +507,36 @@ pub fn next_chunk_index(chunks: &ComponentChunks) -> ChunkIndex {
 #[derive(Clone, PartialEq, Eq)]
 pub struct EntityLayout { 
     pub components: Vec<ComponentTypeId>,
-    component_sizes: Vec<usize>
+    component_sizes: Vec<usize>,
+    component_aligns: Vec<usize>,
+    pub shared: Vec<SharedComponentValue>
+}
+
+pub fn create_empty_entity_layout() -> EntityLayout {
+    EntityLayout {
+        components: Vec::<ComponentTypeId>::default(),
+        component_sizes: Vec::<usize>::default(),
+        component_aligns: Vec::<usize>::default(),
+        shared: Vec::<SharedComponentValue>::default(),
+    }
 }
 
 pub fn create_entity_layout_from_component<C>() -> EntityLayout
 where C: Component {
-    create_entity_layout_from_component_and_size(component_type_of::<C>(), size_of::<C>())
+    create_entity_layout_from_component_and_size(component_type_of::<C>(), size_of::<C>(), align_of::<C>())
 }
 
-fn create_entity_layout_from_component_and_size(component: ComponentTypeId, size: usize) -> EntityLayout {
+fn create_entity_layout_from_component_and_size(component: ComponentTypeId, size: usize, align: usize) -> EntityLayout {
     let mut components = Vec::<ComponentTypeId>::default();
     components.push(component);
     let mut component_sizes = Vec::<usize>::default();
     component_sizes.push(size);
+    let component_aligns = vec!(align);
     EntityLayout {
         components,
         component_sizes,
+        component_aligns,
+        shared: Vec::<SharedComponentValue>::default(),
     }
 }
 
@@ -268,6 +548,21 @@ pub fn copy_layout_components(layout: &EntityLayout) -> Copied<Iter<ComponentTyp
     layout.components.iter().copied()
 }
 
+pub fn layout_shared_component_types(layout: &EntityLayout) -> impl Iterator<Item = ComponentTypeId> + '_ {
+    layout.shared.iter().map(|value| value.component_type_id)
+}
+
+pub fn get_layout_shared_value(layout: &EntityLayout, component_type_id: ComponentTypeId) -> Option<&SharedComponentValue> {
+    layout.shared
+        .iter()
+        .find(|value| value.component_type_id == component_type_id)
+}
+
+pub fn layout_shared_values_match(layout: &EntityLayout, other: &EntityLayout) -> bool {
+    layout.shared.len() == other.shared.len()
+        && layout.shared.iter().all(|value| get_layout_shared_value(other, value.component_type_id) == Some(value))
+}
+
 fn component_type_and_size_iter<'a>(layout: &'a EntityLayout) -> Zip<Iter<'a, ComponentTypeId>, Iter<'a, usize>> {
     layout.components.iter().zip(&layout.component_sizes)
 }
@@ -277,15 +572,52 @@ where C: Component {
     let mut cloned = layout.clone();
     cloned.components.push(component_type_of::<C>());
     cloned.component_sizes.push(size_of::<C>());
+    cloned.component_aligns.push(align_of::<C>());
     cloned
 }
 
+pub fn clone_entity_layout_and_remove_component<C>(layout: &EntityLayout) -> EntityLayout
+where C: Component {
+    let component_type_id = component_type_of::<C>();
+    let (components, (component_sizes, component_aligns)) = component_type_and_size_iter(layout)
+        .zip(&layout.component_aligns)
+        .filter(|((type_id, _), _)| **type_id != component_type_id)
+        .map(|((type_id, size), align)| (*type_id, (*size, *align)))
+        .unzip();
+    EntityLayout {
+        components,
+        component_sizes,
+        component_aligns,
+        shared: layout.shared.clone(),
+    }
+}
+
+pub fn clone_entity_layout_and_set_shared(layout: &EntityLayout, value: SharedComponentValue) -> EntityLayout {
+    let mut cloned = clone_entity_layout_and_remove_shared(layout, value.component_type_id);
+    cloned.shared.push(value);
+    cloned
+}
+
+pub fn clone_entity_layout_and_remove_shared(layout: &EntityLayout, component_type_id: ComponentTypeId) -> EntityLayout {
+    let mut cloned = layout.clone();
+    cloned.shared.retain(|value| value.component_type_id != component_type_id);
+    cloned
+}
+
+pub fn is_entity_layout_empty(layout: &EntityLayout) -> bool {
+    layout.components.is_empty() && layout.shared.is_empty()
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct ComponentTypeId {
     type_id: TypeId,
     name: &'static str,
 }
 
+pub fn component_type_name(component_type_id: ComponentTypeId) -> &'static str {
+    component_type_id.name
+}
+
 #[derive(Default)]
 pub struct ComponentStorages {    
     inner: HashMap<ComponentTypeId, Box<dyn UnknownComponentStorage>>
@@ -295,6 +627,40 @@ pub fn component_storages() -> ComponentStorages {
     ComponentStorages::default()
 }
 
+pub fn clone_component_storages(storages: &ComponentStorages) -> ComponentStorages {
+    ComponentStorages {
+        inner: storages.inner
+            .iter()
+            .map(|(type_id, storage)| (*type_id, storage.duplicate()))
+            .collect()
+    }
+}
+
+pub fn copy_component_storages_for_layout(source: &ComponentStorages, target: &mut ComponentStorages, layout: &EntityLayout) {
+    for type_id in &layout.components {
+        if !target.inner.contains_key(type_id) {
+            target.inner.insert(*type_id, get_component_storage(source, *type_id).duplicate());
+        }
+    }
+}
+
+pub fn drop_components_in_row(storages: &ComponentStorages, layout: &EntityLayout, chunk: &ComponentChunk, component_index: ComponentIndex) {
+    let component_types: HashSet<&ComponentTypeId> = layout.components.iter().collect();
+    for type_id in component_types {
+        get_component_storage(storages, *type_id).drop_component(chunk, component_index);
+    }
+}
+
+pub fn drop_components_in_chunks(storages: &ComponentStorages, layout: &EntityLayout, chunks: &ComponentChunks) {
+    let component_types: HashSet<&ComponentTypeId> = layout.components.iter().collect();
+    for type_id in component_types {
+        let component_storage = get_component_storage(storages, *type_id);
+        for chunk in &chunks.inner {
+            component_storage.drop_components(chunk);
+        }
+    }
+}
+
 
 pub fn get_component_storage(storages: &ComponentStorages, type_id: ComponentTypeId) -> &Box<dyn UnknownComponentStorage> {
     gol_assert!(storages.inner.contains_key(&type_id));
@@ -338,8 +704,11 @@ impl<T: 'static + Sized + Send + Sync> Component for T {
     type Storage = PackedComponentStorage<T>;
 }
 
-pub trait UnknownComponentStorage {
+pub trait UnknownComponentStorage: Send + Sync {
     fn move_component(&self, source_chunks: &ComponentChunks, source_chunk_index: ChunkIndex, source_component_index: ComponentIndex, target_chunks: &ComponentChunks, target_chunk_index: ChunkIndex);
+    fn drop_component(&self, chunk: &ComponentChunk, component_index: ComponentIndex);
+    fn drop_components(&self, chunk: &ComponentChunk);
+    fn duplicate(&self) -> Box<dyn UnknownComponentStorage>;
 }
 
 pub struct PackedComponentStorage<C: Component> {
@@ -366,6 +735,26 @@ impl <T: Component> UnknownComponentStorage for PackedComponentStorage<T> {
         let component = self.swap_remove_component_in_chunk(source_chunk, source_head_chunk, source_component_index);
         self.add_component(target_chunk, target_chunk.current_component_index, component);
     }
+
+    fn drop_component(&self, chunk: &ComponentChunk, component_index: ComponentIndex) {
+        if needs_drop::<T>() {
+            let offset = get_chunk_stream_offset_for_component(self.component_type_id, &chunk.chunk_layout, component_index);
+            drop(take_component_from_chunk_storage::<T>(chunk, offset));
+        }
+    }
+
+    fn drop_components(&self, chunk: &ComponentChunk) {
+        if !needs_drop::<T>() {
+            return;
+        }
+        for index in 0..chunk_entity_count(chunk) {
+            self.drop_component(chunk, ComponentIndex(index));
+        }
+    }
+
+    fn duplicate(&self) -> Box<dyn UnknownComponentStorage> {
+        Box::new(create_packed_component_storage::<T>())
+    }
 }
 
 impl <C> PackedComponentStorage<C> 
@@ -378,7 +767,7 @@ where C: Component {
     fn swap_remove_component_in_chunk(&self, chunk: &ComponentChunk, head_chunk: &ComponentChunk, component_index: ComponentIndex) -> C {
         let item_offset = get_chunk_stream_offset_for_component(self.component_type_id, &chunk.chunk_layout, component_index);
         let head_offset = get_chunk_stream_offset_for_component(self.component_type_id, &head_chunk.chunk_layout, head_chunk.current_component_index);
-        let read = swap_read_component_in_chunk_storage(chunk, chunk, item_offset, head_offset);
+        let read = swap_read_component_in_chunk_storage(chunk, head_chunk, item_offset, head_offset);
         read
     }      
 }
@@ -396,12 +785,18 @@ impl<'a, C:Component> ReadFetch<'a, C> for C {
     }
 }
 
+pub fn fetch_component_mut<C: Component>(chunk: &mut ComponentChunk, component_index: ComponentIndex) -> &mut C {
+    let offset = get_chunk_stream_offset_for_component(component_type_of::<C>(), &chunk.chunk_layout, component_index);
+    unsafe {
+        (chunk.storage.add(offset) as *mut C).as_mut().unwrap()
+    }
+}
+
 fn add_component_in_chunk_storage<C:Component>(chunk: &ComponentChunk, index: usize, component: C) {
-    let removed = unsafe { 
+    unsafe { 
         let pointer = chunk.storage.add(index) as *mut C;
-        *pointer = component
-    };
-    removed
+        std::ptr::write_unaligned(pointer, component);
+    }
 }
 
 fn swap_read_component_in_chunk_storage<C:Component>(source_chunk: &ComponentChunk, target_chunk: &ComponentChunk, source_offset: usize, target_offset: usize) -> C {
@@ -416,6 +811,17 @@ fn swap_read_component_in_chunk_storage<C:Component>(source_chunk: &ComponentChu
     removed
 }
 
+pub fn take_component_in_chunk<C: Component>(chunk: &ComponentChunk, component_index: ComponentIndex) -> C {
+    let offset = get_chunk_stream_offset_for_component(component_type_of::<C>(), &chunk.chunk_layout, component_index);
+    take_component_from_chunk_storage(chunk, offset)
+}
+
+fn take_component_from_chunk_storage<C: Component>(chunk: &ComponentChunk, offset: usize) -> C {
+    unsafe {
+        std::ptr::read_unaligned(chunk.storage.add(offset) as *const C)
+    }
+}
+
 fn get_component_from_chunk_storage<C:Component>(chunk: &ComponentChunk, offset: usize) -> &C {
     unsafe {
         (chunk.storage.add(offset) as *mut C).as_ref().unwrap()
@@ -0,0 +1,186 @@
+use std::fmt;
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DynamicQueryError {
+    UnknownComponent(String),
+}
+
+impl fmt::Display for DynamicQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DynamicQueryError::UnknownComponent(name) => write!(f, "unknown component '{}'", name),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DynamicQuery {
+    pub components: Vec<ComponentTypeId>
+}
+
+pub fn dynamic_query(components: Vec<ComponentTypeId>) -> DynamicQuery {
+    DynamicQuery { components }
+}
+
+pub fn dynamic_query_from_names(world: &World, names: &[&str]) -> Result<DynamicQuery, DynamicQueryError> {
+    let components = names
+        .iter()
+        .map(|name| find_component_type_by_name(world, name).ok_or_else(|| DynamicQueryError::UnknownComponent(name.to_string())))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(dynamic_query(components))
+}
+
+pub fn find_component_type_by_name(world: &World, name: &str) -> Option<ComponentTypeId> {
+    if let Some(registration) = get_component_registration_by_key(&world.registry, name) {
+        return Some(registration.component_type_id);
+    }
+    archetype_iter(&world.archetypes)
+        .flat_map(|archetype| archetype.layout.components.iter())
+        .find(|component_type_id| component_type_name(**component_type_id) == name)
+        .copied()
+}
+
+#[derive(Copy, Clone)]
+pub struct DynamicRow<'a> {
+    pub entity: Entity,
+    pub archetype_index: ArchetypeIndex,
+    components: &'a [ComponentTypeId],
+    chunk: &'a ComponentChunk,
+    component_index: ComponentIndex,
+}
+
+pub fn get_row_component_bytes<'a>(row: &DynamicRow<'a>, component_type_id: ComponentTypeId) -> Option<&'a [u8]> {
+    if !row.components.contains(&component_type_id) {
+        return None;
+    }
+    get_component_bytes_in_chunk(row.chunk, component_type_id, row.component_index)
+}
+
+pub fn row_component_iter<'a>(row: &DynamicRow<'a>) -> impl Iterator<Item = (ComponentTypeId, &'a [u8])> + 'a {
+    let chunk = row.chunk;
+    let component_index = row.component_index;
+    row.components
+        .iter()
+        .map(move |component_type_id| (*component_type_id, get_component_bytes_in_chunk(chunk, *component_type_id, component_index).unwrap()))
+}
+
+pub struct DynamicQueryIterator<'a> {
+    components: &'a [ComponentTypeId],
+    archetypes: ArchetypeIterator<'a>,
+    chunks: Option<(ArchetypeIndex, ArchetypeComponentIterator<'a>)>
+}
+
+pub fn iterate_dynamic_query<'a>(world: &'a World, query: &'a DynamicQuery) -> DynamicQueryIterator<'a> {
+    let filter = any_component_filter(query.components.clone());
+    let archetypes = search_archetypes_for(&world.archetypes.search_index, filter, 0);
+    DynamicQueryIterator {
+        components: &query.components,
+        archetypes: create_archetype_iterator(world, archetypes),
+        chunks: None
+    }
+}
+
+impl<'a> Iterator for DynamicQueryIterator<'a> {
+    type Item = DynamicRow<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((archetype_index, chunk_iter)) = self.chunks.as_mut() {
+            if let Some((chunk, component_index)) = chunk_iter.next() {
+                return Some(DynamicRow {
+                    entity: get_entity_in_chunk(chunk, component_index),
+                    archetype_index: *archetype_index,
+                    components: self.components,
+                    chunk,
+                    component_index
+                });
+            } else {
+                self.chunks = None;
+            }
+        }
+        if let Some(archetype) = self.archetypes.next() {
+            self.chunks = Some((archetype.index, archetype.chunks.component_iter()));
+            return self.next();
+        }
+        None
+    }
+}
+
+pub struct DebugDynamicRow<'a> {
+    registry: &'a ComponentRegistry,
+    row: DynamicRow<'a>
+}
+
+pub fn debug_dynamic_row<'a>(world: &'a World, row: DynamicRow<'a>) -> DebugDynamicRow<'a> {
+    DebugDynamicRow { registry: &world.registry, row }
+}
+
+struct DebugComponentBytes<'a> {
+    registration: Option<&'a ComponentRegistration>,
+    bytes: &'a [u8]
+}
+
+impl<'a> fmt::Debug for DebugComponentBytes<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(result) = self.registration.and_then(|registration| format_registered_component(registration, self.bytes, f)) {
+            return result;
+        }
+        write!(f, "{:02x?}", self.bytes)
+    }
+}
+
+impl<'a> fmt::Debug for DebugDynamicRow<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        map.entry(&"entity", &self.row.entity);
+        for (component_type_id, bytes) in row_component_iter(&self.row) {
+            let registration = get_component_registration(self.registry, component_type_id);
+            let name = registration.map_or(component_type_name(component_type_id), |registration| registration.key);
+            map.entry(&name, &DebugComponentBytes { registration, bytes });
+        }
+        map.finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(u32, u32);
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Alive(bool);
+
+    fn create_test_world() -> World {
+        let mut registry = component_registry();
+        register_component::<Position>(&mut registry, "Position");
+        register_component_debug::<Position>(&mut registry);
+        register_component::<Alive>(&mut registry, "Alive");
+        create_world_with_registry(Arc::new(registry))
+    }
+
+    #[test]
+    fn queries_components_by_name() {
+        let mut world = create_test_world();
+        let first = add_entity(&mut world);
+        add_component(&mut world, first, Position(1, 2));
+        let second = add_entity(&mut world);
+        add_component(&mut world, second, Position(3, 4));
+        add_component(&mut world, second, Alive(true));
+
+        let query = dynamic_query_from_names(&world, &["Position"]).unwrap();
+        let rows: Vec<DynamicRow> = iterate_dynamic_query(&world, &query).collect();
+        assert_eq!(rows.len(), 2);
+
+        let query = dynamic_query_from_names(&world, &["Position", "Alive"]).unwrap();
+        let rows: Vec<DynamicRow> = iterate_dynamic_query(&world, &query).collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].entity, second);
+        assert_eq!(get_row_component_bytes(&rows[0], component_type_of::<Alive>()), Some(&[1u8][..]));
+        assert_eq!(get_row_component_bytes(&rows[0], component_type_of::<Position>()).unwrap().len(), std::mem::size_of::<Position>());
+        assert_eq!(format!("{:?}", debug_dynamic_row(&world, rows[0])), format!("{{\"entity\": {:?}, \"Position\": Position(3, 4), \"Alive\": [01]}}", second));
+
+        assert_eq!(dynamic_query_from_names(&world, &["Velocity"]).unwrap_err(), DynamicQueryError::UnknownComponent("Velocity".to_string()));
+    }
+}
@@ -1,7 +1,7 @@
 use std::marker::*;
 use super::*;
 
-#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Entity {
     index: usize,
 }
@@ -12,6 +12,11 @@ pub fn entity(index: usize) -> Entity {
     }
 }
 
+pub fn entity_index(entity: Entity) -> usize {
+    entity.index
+}
+
+#[derive(Clone)]
 pub struct Entities {
     pub location_map: EntityLocationMap,
     entity_count: usize,
@@ -25,7 +30,8 @@ pub fn entities() -> Entities {
 }
 
 pub fn increment_entity_count(entities: &mut Entities) {
-    entities.entity_count += 1
+    entities.entity_count += 1;
+    entities.location_map.inner.push(None);
 }
 
 pub fn last_entity_index(entities: &Entities) -> usize {
@@ -35,8 +41,9 @@ pub fn last_entity_index(entities: &Entities) -> usize {
 #[derive(Copy, Clone)]
 pub struct EntityLocation { pub archetype_index: ArchetypeIndex, pub location_in_archetype: ArchetypeEntityLocation }
 
+#[derive(Clone)]
 pub struct EntityLocationMap {
-    inner: Vec<EntityLocation>
+    inner: Vec<Option<EntityLocation>>
 }
 
 fn entity_location_map() -> EntityLocationMap {
@@ -46,17 +53,28 @@ fn entity_location_map() -> EntityLocationMap {
 }
 
 pub fn entity_is_located(location_map: &EntityLocationMap, entity: Entity) -> bool {
-    location_map.inner.len() > entity.index
+    matches!(location_map.inner.get(entity.index), Some(Some(_)))
 }
 
 pub fn get_entity_location(location_map: &EntityLocationMap, entity: Entity) -> EntityLocation {
-    location_map.inner[entity.index]
+    location_map.inner[entity.index].unwrap()
 }
 
-pub fn add_entity_to_location(location_map: &mut EntityLocationMap, archetype_index: ArchetypeIndex, location_in_archetype: ArchetypeEntityLocation) {
-    location_map.inner.push(EntityLocation { archetype_index, location_in_archetype });
+pub fn add_entity_to_location(location_map: &mut EntityLocationMap, entity: Entity, archetype_index: ArchetypeIndex, location_in_archetype: ArchetypeEntityLocation) {
+    location_map.inner[entity.index] = Some(EntityLocation { archetype_index, location_in_archetype });
 }
 
 pub fn change_entity_location(location_map: &mut EntityLocationMap, entity: Entity, archetype_index: ArchetypeIndex, location_in_archetype: ArchetypeEntityLocation) {
-    location_map.inner[entity.index] = EntityLocation { archetype_index, location_in_archetype };
+    location_map.inner[entity.index] = Some(EntityLocation { archetype_index, location_in_archetype });
+}
+
+pub fn remove_entity_location(location_map: &mut EntityLocationMap, entity: Entity) {
+    location_map.inner[entity.index] = None;
+}
+
+pub fn entity_location_iter(location_map: &EntityLocationMap) -> impl Iterator<Item = (Entity, EntityLocation)> + '_ {
+    location_map.inner
+        .iter()
+        .enumerate()
+        .filter_map(|(index, location)| location.map(|location| (entity(index), location)))
 }
@@ -0,0 +1,334 @@
+use std::fmt;
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Parent(pub Entity);
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Children(pub Vec<Entity>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HierarchyError {
+    WouldCreateCycle { child: Entity, parent: Entity },
+}
+
+impl fmt::Display for HierarchyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HierarchyError::WouldCreateCycle { child, parent } => write!(f, "{:?} cannot be parented to its own descendant {:?}", child, parent),
+        }
+    }
+}
+
+pub fn register_hierarchy_components(registry: &mut ComponentRegistry) {
+    register_component::<Parent>(registry, "Parent");
+    register_component_serialization(registry, save_parent, load_parent);
+    register_component_entity_references(registry, map_parent);
+    register_component_copy::<Parent>(registry);
+    register_component_debug::<Parent>(registry);
+
+    register_component::<Children>(registry, "Children");
+    register_component_serialization(registry, save_children, load_children);
+    register_component_entity_references(registry, map_children);
+    register_component_clone::<Children>(registry);
+    register_component_debug::<Children>(registry);
+}
+
+fn save_parent(parent: &Parent, writer: &mut SnapshotWriter) {
+    writer.write_entity(parent.0);
+}
+
+fn load_parent(reader: &mut SnapshotReader) -> Result<Parent, SnapshotError> {
+    Ok(Parent(reader.read_entity()?))
+}
+
+fn map_parent(parent: &mut Parent, map: &dyn Fn(Entity) -> Entity) {
+    parent.0 = map(parent.0);
+}
+
+fn save_children(children: &Children, writer: &mut SnapshotWriter) {
+    writer.write_u32(children.0.len() as u32);
+    for child in &children.0 {
+        writer.write_entity(*child);
+    }
+}
+
+fn load_children(reader: &mut SnapshotReader) -> Result<Children, SnapshotError> {
+    let count = reader.read_u32()?;
+    let children = (0..count)
+        .map(|_| reader.read_entity())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Children(children))
+}
+
+fn map_children(children: &mut Children, map: &dyn Fn(Entity) -> Entity) {
+    for child in children.0.iter_mut() {
+        *child = map(*child);
+    }
+}
+
+pub fn set_parent(world: &mut World, child: Entity, parent: Entity) -> Result<(), HierarchyError> {
+    if child == parent || ancestors(world, parent).contains(&child) {
+        return Err(HierarchyError::WouldCreateCycle { child, parent });
+    }
+
+    remove_parent(world, child);
+    let index = get_children(world, parent).len();
+    insert_child(world, child, parent, index);
+    Ok(())
+}
+
+fn insert_child(world: &mut World, child: Entity, parent: Entity, index: usize) {
+    add_component(world, child, Parent(parent));
+    if let Some(children) = get_component_mut::<Children>(world, parent) {
+        children.0.insert(index, child);
+    } else {
+        add_component(world, parent, Children(vec!(child)));
+    }
+}
+
+pub fn remove_parent(world: &mut World, child: Entity) -> Option<Entity> {
+    let Parent(parent) = remove_component::<Parent>(world, child)?;
+    let mut no_children_left = false;
+    if let Some(children) = get_component_mut::<Children>(world, parent) {
+        children.0.retain(|sibling| *sibling != child);
+        no_children_left = children.0.is_empty();
+    }
+    if no_children_left {
+        remove_component::<Children>(world, parent);
+    }
+    Some(parent)
+}
+
+pub(crate) fn detach_from_hierarchy(world: &mut World, entity: Entity) {
+    remove_parent(world, entity);
+    if let Some(Children(children)) = remove_component::<Children>(world, entity) {
+        for child in children {
+            if get_parent(world, child) == Some(entity) {
+                remove_component::<Parent>(world, child);
+            }
+        }
+    }
+}
+
+pub(crate) fn link_cloned_hierarchy(world: &mut World, cloned: &[Entity]) {
+    for clone in cloned {
+        if let Some(parent) = get_parent(world, *clone).filter(|parent| !cloned.contains(parent)) {
+            remove_component::<Parent>(world, *clone);
+            set_parent(world, *clone, parent).unwrap();
+        }
+
+        let mut no_children_left = false;
+        if let Some(children) = get_component_mut::<Children>(world, *clone) {
+            children.0.retain(|child| cloned.contains(child));
+            no_children_left = children.0.is_empty();
+        }
+        if no_children_left {
+            remove_component::<Children>(world, *clone);
+        }
+    }
+}
+
+pub fn get_parent(world: &World, entity: Entity) -> Option<Entity> {
+    get_component::<Parent>(world, entity).map(|parent| parent.0)
+}
+
+pub fn get_children(world: &World, entity: Entity) -> &[Entity] {
+    get_component::<Children>(world, entity).map_or(&[], |children| children.0.as_slice())
+}
+
+pub fn ancestors(world: &World, entity: Entity) -> Vec<Entity> {
+    let mut ancestors = Vec::<Entity>::default();
+    let mut current = entity;
+    while let Some(parent) = get_parent(world, current) {
+        ancestors.push(parent);
+        current = parent;
+    }
+    ancestors
+}
+
+pub fn get_root(world: &World, entity: Entity) -> Entity {
+    ancestors(world, entity).last().copied().unwrap_or(entity)
+}
+
+pub fn descendants(world: &World, entity: Entity) -> Vec<Entity> {
+    let mut descendants = Vec::<Entity>::default();
+    let mut stack: Vec<Entity> = get_children(world, entity).iter().rev().copied().collect();
+    while let Some(current) = stack.pop() {
+        descendants.push(current);
+        stack.extend(get_children(world, current).iter().rev());
+    }
+    descendants
+}
+
+pub fn entity_and_descendants(world: &World, entity: Entity) -> Vec<Entity> {
+    let mut entities = vec!(entity);
+    entities.extend(descendants(world, entity));
+    entities
+}
+
+pub fn despawn_recursive(world: &mut World, entity: Entity) {
+    remove_parent(world, entity);
+    for entity in entity_and_descendants(world, entity) {
+        despawn(world, entity);
+    }
+}
+
+pub fn move_hierarchy_to_world(source: &mut World, target: &mut World, root: Entity) -> Result<Entity, TransferError> {
+    let entities = entity_and_descendants(source, root);
+    let index = get_parent(source, root).map(|parent| get_children(source, parent).iter().position(|child| *child == root).unwrap());
+    let parent = remove_parent(source, root);
+    let moved = move_entities_to_world(source, target, &entities);
+    if let (Err(_), Some(parent), Some(index)) = (&moved, parent, index) {
+        insert_child(source, root, parent, index);
+    }
+    Ok(moved?[0])
+}
+
+pub fn copy_hierarchy_to_world(source: &World, target: &mut World, root: Entity) -> Result<Entity, TransferError> {
+    Ok(copy_detached_hierarchy_to_world(source, target, &entity_and_descendants(source, root))?[0])
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Name(String);
+
+    fn create_test_world() -> World {
+        let mut registry = component_registry();
+        register_hierarchy_components(&mut registry);
+        register_component::<Name>(&mut registry, "Name");
+        register_component_clone::<Name>(&mut registry);
+        create_world_with_registry(Arc::new(registry))
+    }
+
+    fn spawn_named(world: &mut World, name: &str) -> Entity {
+        let entity = add_entity(world);
+        add_component(world, entity, Name(name.to_string()));
+        entity
+    }
+
+    fn spawn_glider(world: &mut World) -> (Entity, Vec<Entity>) {
+        let glider = spawn_named(world, "glider");
+        let cells: Vec<Entity> = (0..5)
+            .map(|index| {
+                let cell = spawn_named(world, &format!("cell {}", index));
+                set_parent(world, cell, glider).unwrap();
+                cell
+            })
+            .collect();
+        (glider, cells)
+    }
+
+    #[test]
+    fn parenting_keeps_children_in_sync() {
+        let mut world = create_test_world();
+        let (glider, cells) = spawn_glider(&mut world);
+        let other = spawn_named(&mut world, "other");
+
+        assert_eq!(get_children(&world, glider), cells.as_slice());
+        assert_eq!(get_parent(&world, cells[2]), Some(glider));
+
+        set_parent(&mut world, cells[2], other).unwrap();
+        assert_eq!(get_children(&world, glider).len(), 4);
+        assert_eq!(get_children(&world, other), &[cells[2]]);
+        assert_eq!(get_root(&world, cells[2]), other);
+
+        assert_eq!(remove_parent(&mut world, cells[2]), Some(other));
+        assert_eq!(get_component::<Children>(&world, other), None);
+        assert_eq!(get_component::<Name>(&world, cells[2]), Some(&Name("cell 2".to_string())));
+
+        set_parent(&mut world, glider, other).unwrap();
+        assert_eq!(set_parent(&mut world, other, cells[0]), Err(HierarchyError::WouldCreateCycle { child: other, parent: cells[0] }));
+        assert_eq!(descendants(&world, other), vec!(glider, cells[0], cells[1], cells[3], cells[4]));
+    }
+
+    #[test]
+    fn despawning_recursively_removes_the_whole_pattern() {
+        let mut world = create_test_world();
+        let board = spawn_named(&mut world, "board");
+        let (glider, cells) = spawn_glider(&mut world);
+        let bystander = spawn_named(&mut world, "bystander");
+        set_parent(&mut world, glider, board).unwrap();
+
+        despawn_recursive(&mut world, glider);
+
+        assert_eq!(get_component::<Name>(&world, glider), None);
+        for cell in cells {
+            assert_eq!(get_component::<Name>(&world, cell), None);
+        }
+        assert_eq!(get_children(&world, board), &[]);
+        assert_eq!(get_component::<Name>(&world, bystander), Some(&Name("bystander".to_string())));
+        assert_eq!(world_stats(&world).entity_count, 2);
+    }
+
+    #[test]
+    fn hierarchies_move_and_copy_as_a_unit() {
+        let mut world = create_test_world();
+        let mut clipboard = create_world_with_registry(get_registry(&world).clone());
+        let board = spawn_named(&mut world, "board");
+        let (glider, _) = spawn_glider(&mut world);
+        set_parent(&mut world, glider, board).unwrap();
+
+        let copied = copy_hierarchy_to_world(&world, &mut clipboard, glider).unwrap();
+        assert_eq!(get_parent(&clipboard, copied), None);
+        assert_eq!(get_children(&clipboard, copied).len(), 5);
+        for cell in get_children(&clipboard, copied) {
+            assert_eq!(get_parent(&clipboard, *cell), Some(copied));
+        }
+
+        let moved = move_hierarchy_to_world(&mut world, &mut clipboard, glider).unwrap();
+        assert_eq!(get_children(&world, board), &[]);
+        assert_eq!(world_stats(&world).entity_count, 1);
+        assert_eq!(descendants(&clipboard, moved).len(), 5);
+        assert_eq!(world_stats(&clipboard).entity_count, 12);
+    }
+
+    #[test]
+    fn despawning_detaches_from_the_hierarchy() {
+        let mut world = create_test_world();
+        let (glider, cells) = spawn_glider(&mut world);
+
+        despawn(&mut world, cells[2]);
+        assert_eq!(get_children(&world, glider), &[cells[0], cells[1], cells[3], cells[4]]);
+
+        despawn(&mut world, glider);
+        for cell in [cells[0], cells[1], cells[3], cells[4]] {
+            assert_eq!(get_parent(&world, cell), None);
+        }
+    }
+
+    #[test]
+    fn failed_moves_keep_the_child_in_place() {
+        let mut world = create_test_world();
+        let mut clipboard = create_world();
+        let board = spawn_named(&mut world, "board");
+        let before = spawn_named(&mut world, "before");
+        let (glider, _) = spawn_glider(&mut world);
+        let after = spawn_named(&mut world, "after");
+        for child in [before, glider, after] {
+            set_parent(&mut world, child, board).unwrap();
+        }
+
+        assert!(move_hierarchy_to_world(&mut world, &mut clipboard, glider).is_err());
+        assert_eq!(get_children(&world, board), &[before, glider, after]);
+        assert_eq!(get_parent(&world, glider), Some(board));
+    }
+
+    #[test]
+    fn clones_join_the_original_parent() {
+        let mut world = create_test_world();
+        let (glider, cells) = spawn_glider(&mut world);
+
+        let cell = clone_entity(&mut world, cells[0]).unwrap();
+        assert_eq!(get_parent(&world, cell), Some(glider));
+        assert_eq!(get_children(&world, glider).last(), Some(&cell));
+
+        let clone = clone_entity(&mut world, glider).unwrap();
+        assert_eq!(get_children(&world, clone), &[]);
+        assert_eq!(get_parent(&world, cells[1]), Some(glider));
+    }
+}
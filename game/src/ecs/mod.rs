@@ -3,28 +3,79 @@ mod archetypes;
 mod components;
 mod querying;
 mod slicevec;
+mod stats;
+mod compaction;
+mod resources;
+mod registry;
+mod serialization;
+mod scene;
+mod dynamic;
+mod cloning;
+mod transfer;
+mod hierarchy;
+mod shared;
+mod prefab;
+mod validation;
+mod ordering;
+#[cfg(test)]
+mod model_test;
 
 pub use entities::*;
 pub use archetypes::*;
 pub use components::*;
 pub use querying::*;
+pub use stats::*;
+pub use compaction::*;
+pub use resources::*;
+pub use registry::*;
+pub use serialization::*;
+pub use scene::*;
+pub use dynamic::*;
+pub use cloning::*;
+pub use transfer::*;
+pub use hierarchy::*;
+pub use shared::*;
+pub use prefab::*;
+pub use validation::*;
+pub use ordering::*;
+
+use std::sync::Arc;
 
 #[repr(C)]
 pub struct World {
     entities: Entities,
     archetypes: Archetypes,
     component_storages: ComponentStorages,
-    
+    resources: Resources,
+    registry: Arc<ComponentRegistry>,
+}
+
+impl Drop for World {
+    fn drop(&mut self) {
+        for archetype in archetype_iter(&self.archetypes) {
+            drop_components_in_chunks(&self.component_storages, &archetype.layout, &archetype.chunks);
+        }
+    }
 }
 
 pub fn create_world() -> World {
+    create_world_with_registry(Arc::new(component_registry()))
+}
+
+pub fn create_world_with_registry(registry: Arc<ComponentRegistry>) -> World {
     World {
         entities: entities(),        
         archetypes: archetypes(),
         component_storages: component_storages(),
+        resources: resources(),
+        registry,
     }
 }
 
+pub fn get_registry(world: &World) -> &Arc<ComponentRegistry> {
+    &world.registry
+}
+
 pub fn add_entity(world: &mut World) -> Entity {
     increment_entity_count(&mut world.entities);
     entity(last_entity_index(&world.entities))
@@ -35,6 +86,11 @@ pub fn add_component<C>(world: &mut World, entity: Entity, component: C)
     
     set_component_storage_if_not_set_already::<C>(&mut world.component_storages);
 
+    if let Some(existing) = get_component_mut::<C>(world, entity) {
+        *existing = component;
+        return;
+    }
+
     if entity_is_located(&world.entities.location_map, entity) {
         let location = get_entity_location(&world.entities.location_map, entity);
         let source_archetype = get_archetype_for_entity_location(&world.archetypes, location);
@@ -49,16 +105,181 @@ pub fn add_component<C>(world: &mut World, entity: Entity, component: C)
         let source_archetype = get_archetype_for_entity_location(&world.archetypes, location);
             
         move_components(&mut world.component_storages, source_layout, source_archetype, location.location_in_archetype, target_archetype, target_archetype_entity_location);
+        let swapped_entity = swap_remove_entity_at_location(&source_archetype.chunks, location.location_in_archetype);
+        store_entity_at_location(&target_archetype.chunks, target_archetype_entity_location, entity);
         store_component_at_location(&target_archetype.chunks, target_archetype_entity_location, component);
         move_to_previous_archetype_entity_location(&mut world.archetypes, source_archetype_index); 
         change_entity_location(&mut world.entities.location_map, entity, target_archetype_index, target_archetype_entity_location);
+        if let Some(swapped_entity) = swapped_entity {
+            change_entity_location(&mut world.entities.location_map, swapped_entity, source_archetype_index, location.location_in_archetype);
+        }
     } else {
         let layout = create_entity_layout_from_component::<C>();
         create_archetype_if_non_existant(&mut world.archetypes, &layout);     
         let archetype_entity_location = move_to_next_archetype_entity_location(&mut world.archetypes, &layout);
         let archetype = get_or_create_archetype_mut(&mut world.archetypes, &layout);
+        store_entity_at_location(&archetype.chunks, archetype_entity_location, entity);
         store_component_at_location::<C>(&archetype.chunks, archetype_entity_location, component);
-        add_entity_to_location(&mut world.entities.location_map, archetype.index, archetype_entity_location);
+        add_entity_to_location(&mut world.entities.location_map, entity, archetype.index, archetype_entity_location);
+    }
+    validate_structural_change(world);
+}
+
+pub fn remove_component<C>(world: &mut World, entity: Entity) -> Option<C>
+    where C: Component {
+
+    if !entity_is_located(&world.entities.location_map, entity) {
+        return None;
+    }
+
+    let location = get_entity_location(&world.entities.location_map, entity);
+    let source_archetype = get_archetype_for_entity_location(&world.archetypes, location);
+    if !source_archetype.layout.components.contains(&component_type_of::<C>()) {
+        return None;
     }
+
+    let source_chunk = get_chunk(&source_archetype.chunks, location.location_in_archetype.chunk);
+    let component = take_component_in_chunk::<C>(source_chunk, location.location_in_archetype.component);
+    let target_layout = clone_entity_layout_and_remove_component::<C>(&source_archetype.layout);
+    if is_entity_layout_empty(&target_layout) {
+        remove_entity_row(world, entity);
+    } else {
+        move_entity_to_layout(world, entity, &target_layout);
+    }
+    validate_structural_change(world);
+    Some(component)
+}
+
+pub fn despawn(world: &mut World, entity: Entity) {
+    if !entity_is_located(&world.entities.location_map, entity) {
+        return;
+    }
+
+    detach_from_hierarchy(world, entity);
+    run_component_hooks(world, entity, despawn_registered_component);
+    despawn_row(world, entity);
+}
+
+fn run_component_hooks(world: &mut World, entity: Entity, hook: fn(&ComponentRegistration, &mut World, Entity)) {
+    let location = get_entity_location(&world.entities.location_map, entity);
+    let components = get_archetype_for_entity_location(&world.archetypes, location).layout.components.clone();
+    let registry = world.registry.clone();
+    for component_type_id in components {
+        if let Some(registration) = get_component_registration(&registry, component_type_id) {
+            hook(registration, world, entity);
+        }
+    }
+}
+
+fn despawn_row(world: &mut World, entity: Entity) {
+    let location = get_entity_location(&world.entities.location_map, entity);
+    let archetype = get_archetype_for_entity_location(&world.archetypes, location);
+    let chunk = get_chunk(&archetype.chunks, location.location_in_archetype.chunk);
+    drop_components_in_row(&world.component_storages, &archetype.layout, chunk, location.location_in_archetype.component);
+    remove_entity_row(world, entity);
+    validate_structural_change(world);
+}
+
+#[cfg(feature = "gol-slow")]
+fn validate_structural_change(world: &World) {
+    validate_world(world);
+}
+
+#[cfg(not(feature = "gol-slow"))]
+fn validate_structural_change(_world: &World) {
 }
 
+fn move_entity_to_layout(world: &mut World, entity: Entity, target_layout: &EntityLayout) {
+    create_archetype_if_non_existant(&mut world.archetypes, target_layout);
+    let target_archetype_entity_location = move_to_next_archetype_entity_location(&mut world.archetypes, target_layout);
+    let target_archetype = get_archetype_for_layout(&world.archetypes, target_layout).unwrap();
+    let target_archetype_index = target_archetype.index;
+    let target_chunk = get_chunk(&target_archetype.chunks, target_archetype_entity_location.chunk);
+
+    if entity_is_located(&world.entities.location_map, entity) {
+        let location = get_entity_location(&world.entities.location_map, entity);
+        let source_archetype = get_archetype_for_entity_location(&world.archetypes, location);
+        let source_chunk = get_chunk(&source_archetype.chunks, location.location_in_archetype.chunk);
+        copy_row_between_chunks(source_chunk, location.location_in_archetype.component, target_chunk, target_archetype_entity_location.component);
+        remove_entity_row(world, entity);
+    } else {
+        store_entity_at_location(&target_archetype.chunks, target_archetype_entity_location, entity);
+    }
+    add_entity_to_location(&mut world.entities.location_map, entity, target_archetype_index, target_archetype_entity_location);
+}
+
+fn remove_entity_row(world: &mut World, entity: Entity) {
+    let location = get_entity_location(&world.entities.location_map, entity);
+    let archetype = get_archetype_for_entity_location(&world.archetypes, location);
+    let archetype_index = archetype.index;
+    let swapped_entity = swap_remove_row_at_location(&archetype.chunks, location.location_in_archetype);
+    move_to_previous_archetype_entity_location(&mut world.archetypes, archetype_index);
+    remove_entity_location(&mut world.entities.location_map, entity);
+    if let Some(swapped_entity) = swapped_entity {
+        change_entity_location(&mut world.entities.location_map, swapped_entity, archetype_index, location.location_in_archetype);
+    }
+}
+
+pub fn get_component<C>(world: &World, entity: Entity) -> Option<&C>
+    where C: Component {
+    
+    if !entity_is_located(&world.entities.location_map, entity) {
+        return None;
+    }
+
+    let location = get_entity_location(&world.entities.location_map, entity);
+    let archetype = get_archetype_for_entity_location(&world.archetypes, location);
+    if !archetype.layout.components.contains(&component_type_of::<C>()) {
+        return None;
+    }
+
+    let chunk = get_chunk(&archetype.chunks, location.location_in_archetype.chunk);
+    Some(C::fetch(chunk, location.location_in_archetype.component))
+}
+
+pub fn get_component_mut<C>(world: &mut World, entity: Entity) -> Option<&mut C>
+    where C: Component {
+    
+    if !entity_is_located(&world.entities.location_map, entity) {
+        return None;
+    }
+
+    let location = get_entity_location(&world.entities.location_map, entity);
+    let archetype = get_archetype_for_entity_location_mut(&mut world.archetypes, location);
+    if !archetype.layout.components.contains(&component_type_of::<C>()) {
+        return None;
+    }
+
+    let chunk = get_chunk_mut(&mut archetype.chunks, location.location_in_archetype.chunk);
+    Some(fetch_component_mut(chunk, location.location_in_archetype.component))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(usize);
+    #[derive(Debug, PartialEq)]
+    struct Alive(bool);
+
+    #[test]
+    fn migrating_entities_keeps_swapped_locations() {
+        let mut world = create_world();
+        let entities: Vec<Entity> = (0..3)
+            .map(|index| {
+                let entity = add_entity(&mut world);
+                add_component(&mut world, entity, Position(index));
+                entity
+            })
+            .collect();
+
+        add_component(&mut world, entities[0], Alive(true));
+
+        for (index, entity) in entities.iter().enumerate() {
+            assert_eq!(get_component::<Position>(&world, *entity), Some(&Position(index)));
+        }
+        assert_eq!(get_component::<Alive>(&world, entities[0]), Some(&Alive(true)));
+        assert_eq!(get_component::<Alive>(&world, entities[1]), None);
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::*;
+
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Small(u8);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Wide(u64);
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct Name(String);
+
+#[derive(Default, Clone, Debug)]
+struct ModelEntity {
+    small: Option<Small>,
+    wide: Option<Wide>,
+    name: Option<Name>,
+}
+
+impl ModelEntity {
+    fn is_empty(&self) -> bool {
+        self.small.is_none() && self.wide.is_none() && self.name.is_none()
+    }
+}
+
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, count: usize) -> usize {
+        (self.next() % count as u64) as usize
+    }
+}
+
+struct Harness {
+    world: World,
+    model: HashMap<Entity, ModelEntity>,
+    spawned: Vec<Entity>,
+    random: Random,
+}
+
+fn harness(seed: u64) -> Harness {
+    Harness {
+        world: create_world(),
+        model: HashMap::default(),
+        spawned: Vec::default(),
+        random: Random(seed.max(1)),
+    }
+}
+
+fn pick_entity(harness: &mut Harness) -> Option<Entity> {
+    if harness.spawned.is_empty() {
+        return None;
+    }
+    let index = harness.random.below(harness.spawned.len());
+    Some(harness.spawned[index])
+}
+
+fn apply_random_operation(harness: &mut Harness) {
+    let value = harness.random.next();
+    let entity = match pick_entity(harness) {
+        Some(entity) if harness.random.below(8) != 0 => entity,
+        _ => {
+            let entity = add_entity(&mut harness.world);
+            harness.spawned.push(entity);
+            harness.model.insert(entity, ModelEntity::default());
+            entity
+        }
+    };
+    let model = harness.model.entry(entity).or_default();
+
+    match harness.random.below(8) {
+        0 => {
+            add_component(&mut harness.world, entity, Small(value as u8));
+            model.small = Some(Small(value as u8));
+        }
+        1 => {
+            add_component(&mut harness.world, entity, Wide(value));
+            model.wide = Some(Wide(value));
+        }
+        2 => {
+            add_component(&mut harness.world, entity, Name(format!("entity {}", value)));
+            model.name = Some(Name(format!("entity {}", value)));
+        }
+        3 => assert_eq!(remove_component::<Small>(&mut harness.world, entity), model.small.take()),
+        4 => assert_eq!(remove_component::<Wide>(&mut harness.world, entity), model.wide.take()),
+        5 => assert_eq!(remove_component::<Name>(&mut harness.world, entity), model.name.take()),
+        6 => {
+            despawn(&mut harness.world, entity);
+            *model = ModelEntity::default();
+        }
+        _ => {
+            compact_world(&mut harness.world, DEFAULT_CHUNK_POOL_LIMIT);
+        }
+    }
+}
+
+fn check_world_matches_model(harness: &Harness) {
+    let world = &harness.world;
+    for (entity, model) in &harness.model {
+        assert_eq!(get_component::<Small>(world, *entity), model.small.as_ref(), "{:?}", entity);
+        assert_eq!(get_component::<Wide>(world, *entity), model.wide.as_ref(), "{:?}", entity);
+        assert_eq!(get_component::<Name>(world, *entity), model.name.as_ref(), "{:?}", entity);
+    }
+
+    let live_count = harness.model.values().filter(|model| !model.is_empty()).count();
+    assert_eq!(world_stats(world).entity_count, live_count);
+
+    let mut expected: Vec<(Small, Wide)> = harness.model
+        .values()
+        .filter_map(|model| Some((model.small?, model.wide?)))
+        .collect();
+    let mut queried: Vec<(Small, Wide)> = iterate_query::<Small, Wide>(world)
+        .map(|(small, wide)| (*small, *wide))
+        .collect();
+    expected.sort();
+    queried.sort();
+    assert_eq!(queried, expected);
+
+    let query = dynamic_query(vec!(component_type_of::<Name>()));
+    let mut expected: Vec<Entity> = harness.model
+        .iter()
+        .filter(|(_, model)| model.name.is_some())
+        .map(|(entity, _)| *entity)
+        .collect();
+    let mut queried: Vec<Entity> = iterate_dynamic_query(world, &query)
+        .map(|row| row.entity)
+        .collect();
+    expected.sort_by_key(|entity| entity_index(*entity));
+    queried.sort_by_key(|entity| entity_index(*entity));
+    assert_eq!(queried, expected);
+}
+
+fn run_model_test(seed: u64, operations: usize) {
+    let mut harness = harness(seed);
+    for _ in 0..operations {
+        apply_random_operation(&mut harness);
+        check_world_matches_model(&harness);
+    }
+}
+
+#[test]
+fn random_operations_match_reference_model() {
+    for seed in 1..=8u64 {
+        run_model_test(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15), 2000);
+    }
+}
+
+#[test]
+fn large_worlds_match_reference_model() {
+    let mut harness = harness(0x2545_f491_4f6c_dd1d);
+    for _ in 0..20000 {
+        apply_random_operation(&mut harness);
+    }
+    check_world_matches_model(&harness);
+}
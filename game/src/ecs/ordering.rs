@@ -0,0 +1,163 @@
+use std::{
+    cmp::Ordering,
+    marker::PhantomData
+};
+
+use super::*;
+
+pub struct OrderedQueryIterator<'a, T: View<'a>> {
+    data: PhantomData<T>,
+    rows: std::vec::IntoIter<(&'a ComponentChunk, ComponentIndex)>
+}
+
+impl<'a, T: View<'a, Fetch = T>> Iterator for OrderedQueryIterator<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.rows.next().map(|(chunk, component_index)| T::fetch(chunk, component_index))
+    }
+}
+
+pub fn iterate_query_by_entity<'a, A:Component, B:Component>(world: &'a World) -> OrderedQueryIterator<'a, (&'a A, &'a B)> {
+    let filter = any_component_filter(vec!(component_type_of::<A>(), component_type_of::<B>()));
+    create_ordered_query_iterator(world, filter, compare_rows_by_entity)
+}
+
+pub fn iterate_query_by_key<'a, K:Component + Ord, A:Component, B:Component>(world: &'a World) -> OrderedQueryIterator<'a, (&'a A, &'a B)> {
+    let filter = any_component_filter(vec!(component_type_of::<K>(), component_type_of::<A>(), component_type_of::<B>()));
+    create_ordered_query_iterator(world, filter, compare_rows_by_key::<K>)
+}
+
+fn create_ordered_query_iterator<'a, T: View<'a>>(
+    world: &'a World,
+    filter: AnyComponentFilter,
+    compare: impl Fn((&ComponentChunk, ComponentIndex), (&ComponentChunk, ComponentIndex)) -> Ordering
+) -> OrderedQueryIterator<'a, T> {
+    let archetypes = search_archetypes_for(&world.archetypes.search_index, filter, 0);
+    let mut rows: Vec<(&'a ComponentChunk, ComponentIndex)> = create_archetype_iterator(world, archetypes)
+        .flat_map(|archetype| archetype.chunks.component_iter())
+        .collect();
+    rows.sort_by(|a, b| compare(*a, *b));
+    OrderedQueryIterator {
+        data: PhantomData,
+        rows: rows.into_iter()
+    }
+}
+
+pub fn sort_archetype_by_entity(world: &mut World, archetype_index: ArchetypeIndex) -> usize {
+    sort_archetype_rows(world, archetype_index, compare_rows_by_entity)
+}
+
+pub fn sort_archetype_by_key<K: Component + Ord>(world: &mut World, archetype_index: ArchetypeIndex) -> usize {
+    if !get_archetype(&world.archetypes, archetype_index).layout.components.contains(&component_type_of::<K>()) {
+        return sort_archetype_by_entity(world, archetype_index);
+    }
+    sort_archetype_rows(world, archetype_index, compare_rows_by_key::<K>)
+}
+
+pub fn sort_world_by_entity(world: &mut World) -> usize {
+    archetype_indicies(world)
+        .into_iter()
+        .map(|archetype_index| sort_archetype_by_entity(world, archetype_index))
+        .sum()
+}
+
+pub fn sort_world_by_key<K: Component + Ord>(world: &mut World) -> usize {
+    archetype_indicies(world)
+        .into_iter()
+        .map(|archetype_index| sort_archetype_by_key::<K>(world, archetype_index))
+        .sum()
+}
+
+fn archetype_indicies(world: &World) -> Vec<ArchetypeIndex> {
+    archetype_iter(&world.archetypes)
+        .map(|archetype| archetype.index)
+        .collect()
+}
+
+fn sort_archetype_rows(
+    world: &mut World,
+    archetype_index: ArchetypeIndex,
+    compare: impl FnMut((&ComponentChunk, ComponentIndex), (&ComponentChunk, ComponentIndex)) -> Ordering
+) -> usize {
+    let location_map = &mut world.entities.location_map;
+    let rows_moved = sort_archetype_chunks(&world.archetypes, archetype_index, compare, |entity, location| {
+        change_entity_location(location_map, entity, archetype_index, location);
+    });
+    validate_structural_change(world);
+    rows_moved
+}
+
+fn compare_rows_by_entity(a: (&ComponentChunk, ComponentIndex), b: (&ComponentChunk, ComponentIndex)) -> Ordering {
+    entity_index(get_entity_in_chunk(a.0, a.1)).cmp(&entity_index(get_entity_in_chunk(b.0, b.1)))
+}
+
+fn compare_rows_by_key<K: Component + Ord>(a: (&ComponentChunk, ComponentIndex), b: (&ComponentChunk, ComponentIndex)) -> Ordering {
+    <K as ReadFetch<K>>::fetch(a.0, a.1)
+        .cmp(<K as ReadFetch<K>>::fetch(b.0, b.1))
+        .then_with(|| compare_rows_by_entity(a, b))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(usize);
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Alive(bool);
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    struct Priority(usize);
+
+    fn build_shuffled_world(count: usize) -> (World, Vec<Entity>) {
+        let mut world = create_world();
+        let entities: Vec<Entity> = (0..count).map(|_| add_entity(&mut world)).collect();
+        for (index, entity) in entities.iter().enumerate().rev() {
+            add_component(&mut world, *entity, Position(index));
+            add_component(&mut world, *entity, Priority((index * 7) % 11));
+        }
+        for (index, entity) in entities.iter().enumerate() {
+            if index % 3 != 0 {
+                add_component(&mut world, *entity, Alive(index % 2 == 0));
+            }
+        }
+        for entity in entities.iter().step_by(4) {
+            remove_component::<Alive>(&mut world, *entity);
+            add_component(&mut world, *entity, Alive(true));
+        }
+        (world, entities)
+    }
+
+    #[test]
+    fn ordered_queries_ignore_construction_history() {
+        let (world, _) = build_shuffled_world(2000);
+        let positions: Vec<usize> = iterate_query_by_entity::<Position, Priority>(&world)
+            .map(|(position, _)| position.0)
+            .collect();
+        assert_eq!(positions, (0..2000).collect::<Vec<_>>());
+
+        let keyed: Vec<(usize, usize)> = iterate_query_by_key::<Priority, Priority, Position>(&world)
+            .map(|(priority, position)| (priority.0, position.0))
+            .collect();
+        let mut expected: Vec<(usize, usize)> = (0..2000).map(|index| ((index * 7) % 11, index)).collect();
+        expected.sort();
+        assert_eq!(keyed, expected);
+    }
+
+    #[test]
+    fn sorting_chunks_in_place_orders_plain_iteration() {
+        let (mut world, entities) = build_shuffled_world(2000);
+        assert!(sort_world_by_entity(&mut world) > 0);
+        let positions: Vec<usize> = iterate_query::<Position, Alive>(&world).map(|(position, _)| position.0).collect();
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+
+        sort_world_by_key::<Priority>(&mut world);
+        let priorities: Vec<usize> = iterate_query::<Priority, Alive>(&world).map(|(priority, _)| priority.0).collect();
+        assert!(priorities.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        for (index, entity) in entities.iter().enumerate() {
+            assert_eq!(get_component::<Position>(&world, *entity), Some(&Position(index)));
+        }
+        assert_eq!(sort_world_by_key::<Priority>(&mut world), 0);
+    }
+}
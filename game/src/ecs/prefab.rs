@@ -0,0 +1,137 @@
+use super::*;
+
+pub struct Prefab {
+    world: World,
+    entities: Vec<Entity>
+}
+
+pub fn create_prefab(registry: Arc<ComponentRegistry>) -> Prefab {
+    Prefab {
+        world: create_world_with_registry(registry),
+        entities: Vec::<Entity>::default()
+    }
+}
+
+pub fn capture_prefab(world: &World, entities: &[Entity]) -> Result<Prefab, TransferError> {
+    let mut prefab = create_prefab(get_registry(world).clone());
+    prefab.entities = copy_entities_to_world(world, &mut prefab.world, entities)?;
+    Ok(prefab)
+}
+
+pub fn capture_prefab_from_hierarchy(world: &World, root: Entity) -> Result<Prefab, TransferError> {
+    let mut prefab = create_prefab(get_registry(world).clone());
+    prefab.entities = copy_detached_hierarchy_to_world(world, &mut prefab.world, &entity_and_descendants(world, root))?;
+    Ok(prefab)
+}
+
+pub fn add_prefab_entity(prefab: &mut Prefab) -> Entity {
+    let entity = add_entity(&mut prefab.world);
+    prefab.entities.push(entity);
+    entity
+}
+
+pub fn add_prefab_component<C: Component>(prefab: &mut Prefab, entity: Entity, component: C) {
+    add_component(&mut prefab.world, entity, component);
+}
+
+pub fn set_prefab_shared_component<S: SharedComponent>(prefab: &mut Prefab, entity: Entity, value: S) {
+    set_shared_component(&mut prefab.world, entity, value);
+}
+
+pub fn get_prefab_world(prefab: &Prefab) -> &World {
+    &prefab.world
+}
+
+pub fn prefab_entities(prefab: &Prefab) -> &[Entity] {
+    &prefab.entities
+}
+
+pub fn instantiate_prefab(world: &mut World, prefab: &Prefab) -> Result<Vec<Entity>, TransferError> {
+    copy_entities_to_world(&prefab.world, world, &prefab.entities)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(i32, i32);
+    #[derive(Debug, Clone, PartialEq)]
+    struct Name(String);
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Link(Option<Entity>);
+
+    fn create_test_registry() -> Arc<ComponentRegistry> {
+        let mut registry = component_registry();
+        register_hierarchy_components(&mut registry);
+        register_component::<Position>(&mut registry, "Position");
+        register_component_copy::<Position>(&mut registry);
+        register_component::<Name>(&mut registry, "Name");
+        register_component_clone::<Name>(&mut registry);
+        register_component::<Link>(&mut registry, "Link");
+        register_component_copy::<Link>(&mut registry);
+        register_component_entity_references::<Link>(&mut registry, |link, map| link.0 = link.0.map(map));
+        Arc::new(registry)
+    }
+
+    #[test]
+    fn cloning_entity_copies_components_and_self_references() {
+        let mut world = create_world_with_registry(create_test_registry());
+        let entity = add_entity(&mut world);
+        add_component(&mut world, entity, Name("original".to_string()));
+        add_component(&mut world, entity, Link(Some(entity)));
+
+        let cloned = clone_entity(&mut world, entity).unwrap();
+        get_component_mut::<Name>(&mut world, entity).unwrap().0.push_str(" changed");
+
+        assert_ne!(cloned, entity);
+        assert_eq!(get_component::<Name>(&world, cloned), Some(&Name("original".to_string())));
+        assert_eq!(get_component::<Link>(&world, cloned), Some(&Link(Some(cloned))));
+        assert_eq!(get_component::<Link>(&world, entity), Some(&Link(Some(entity))));
+    }
+
+    #[test]
+    fn prefabs_stamp_independent_copies() {
+        let registry = create_test_registry();
+        let mut prefab = create_prefab(registry.clone());
+        let head = add_prefab_entity(&mut prefab);
+        let tail = add_prefab_entity(&mut prefab);
+        add_prefab_component(&mut prefab, head, Position(0, 0));
+        add_prefab_component(&mut prefab, head, Link(Some(tail)));
+        add_prefab_component(&mut prefab, tail, Position(1, 0));
+        add_prefab_component(&mut prefab, tail, Link(Some(head)));
+
+        let mut world = create_world_with_registry(registry);
+        let first = instantiate_prefab(&mut world, &prefab).unwrap();
+        let second = instantiate_prefab(&mut world, &prefab).unwrap();
+
+        assert_eq!(get_component::<Link>(&world, first[0]), Some(&Link(Some(first[1]))));
+        assert_eq!(get_component::<Link>(&world, second[1]), Some(&Link(Some(second[0]))));
+        assert_eq!(get_component::<Position>(&world, second[1]), Some(&Position(1, 0)));
+        assert_eq!(world_stats(&world).entity_count, 4);
+        assert_eq!(world_stats(get_prefab_world(&prefab)).entity_count, 2);
+    }
+
+    #[test]
+    fn prefabs_capture_hierarchies() {
+        let mut world = create_world_with_registry(create_test_registry());
+        let board = add_entity(&mut world);
+        add_component(&mut world, board, Name("board".to_string()));
+        let glider = add_entity(&mut world);
+        add_component(&mut world, glider, Name("glider".to_string()));
+        set_parent(&mut world, glider, board).unwrap();
+        for index in 0..5 {
+            let cell = add_entity(&mut world);
+            add_component(&mut world, cell, Position(index, index));
+            set_parent(&mut world, cell, glider).unwrap();
+        }
+
+        let prefab = capture_prefab_from_hierarchy(&world, glider).unwrap();
+        let stamped = instantiate_prefab(&mut world, &prefab).unwrap();
+
+        assert_eq!(prefab_entities(&prefab).len(), 6);
+        assert_eq!(get_parent(&world, stamped[0]), None);
+        assert_eq!(descendants(&world, stamped[0]), stamped[1..].to_vec());
+        assert_eq!(get_children(&world, board), &[glider]);
+    }
+}
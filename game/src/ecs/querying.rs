@@ -62,6 +62,35 @@ impl<'a, A:Component, B:Component> View<'a> for (&'a A, &'a B) {
     }
 }
 
+pub fn iterate_query_chunks<'a, A:Component, B:Component>(world: &'a World) -> impl Iterator<Item = &'a ComponentChunk> + 'a {
+    let filter = any_component_filter(vec!(component_type_of::<A>(), component_type_of::<B>()));
+    let archetypes = search_archetypes_for(&world.archetypes.search_index, filter, 0);
+    create_archetype_iterator(world, archetypes).flat_map(|archetype| chunk_iter(&archetype.chunks))
+}
+
+pub fn iterate_chunk<'a, A:Component, B:Component>(chunk: &'a ComponentChunk) -> impl Iterator<Item = (&'a A, &'a B)> + 'a {
+    chunk_component_indicies(chunk).map(move |component_index| <(&'a A, &'a B) as View<'a>>::fetch(chunk, component_index))
+}
+
+pub fn iterate_component_entities<'a, A:Component>(world: &'a World) -> impl Iterator<Item = (Entity, &'a A)> + 'a {
+    let filter = any_component_filter(vec!(component_type_of::<A>()));
+    let archetypes = search_archetypes_for(&world.archetypes.search_index, filter, 0);
+    create_archetype_iterator(world, archetypes)
+        .flat_map(|archetype| archetype.chunks.component_iter())
+        .map(|(chunk, component_index)| (get_entity_in_chunk(chunk, component_index), A::fetch(chunk, component_index)))
+}
+
+pub fn iterate_query_entities<'a, A:Component, B:Component>(world: &'a World) -> impl Iterator<Item = (Entity, &'a A, &'a B)> + 'a {
+    iterate_query_chunks::<A, B>(world).flat_map(iterate_chunk_entities::<A, B>)
+}
+
+pub fn iterate_chunk_entities<'a, A:Component, B:Component>(chunk: &'a ComponentChunk) -> impl Iterator<Item = (Entity, &'a A, &'a B)> + 'a {
+    chunk_component_indicies(chunk).map(move |component_index| {
+        let (a, b) = <(&'a A, &'a B) as View<'a>>::fetch(chunk, component_index);
+        (get_entity_in_chunk(chunk, component_index), a, b)
+    })
+}
+
 /*
 pub fn component<T>() -> bool {
     todo!()
@@ -88,15 +117,16 @@ impl FilterResult {
 impl LayoutFilter for EntityLayout {
     fn matches_layout(&self, components: &[ComponentTypeId]) -> FilterResult {
         FilterResult::Match(
-            components.len() == self.components.len()
-                && self.components.iter().all(|t| components.contains(t)),
+            components.len() == self.components.len() + self.shared.len()
+                && self.components.iter().all(|t| components.contains(t))
+                && layout_shared_component_types(self).all(|t| components.contains(&t)),
         )
     }
 }
 
-struct AnyComponentFilter(Vec<ComponentTypeId>);
+pub struct AnyComponentFilter(Vec<ComponentTypeId>);
 
-fn any_component_filter(components: Vec<ComponentTypeId>) -> AnyComponentFilter {
+pub fn any_component_filter(components: Vec<ComponentTypeId>) -> AnyComponentFilter {
     AnyComponentFilter(components)
 }
 impl LayoutFilter for AnyComponentFilter {
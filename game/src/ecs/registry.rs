@@ -0,0 +1,362 @@
+use std::{
+    any::*,
+    fmt,
+    mem::*,
+    ptr
+};
+
+use gol_engine::gol_assert;
+
+use super::*;
+
+pub type SaveComponent<C> = fn(&C, &mut SnapshotWriter);
+pub type LoadComponent<C> = fn(&mut SnapshotReader) -> Result<C, SnapshotError>;
+pub type MapEntities<C> = fn(&mut C, &dyn Fn(Entity) -> Entity);
+pub type ParseComponent<C> = fn(&SceneFields) -> Result<C, SceneErrorKind>;
+pub type DespawnComponent = fn(&mut World, Entity);
+pub type ArriveComponent = fn(&mut World, Entity);
+pub type ParseResource = fn(&mut World, &SceneFields) -> Result<(), SceneErrorKind>;
+pub type SaveResource<R> = fn(&R, &mut SnapshotWriter);
+pub type LoadResource<R> = fn(&mut SnapshotReader) -> Result<R, SnapshotError>;
+
+type ErasedSaveComponent = Box<dyn Fn(&ComponentChunk, ComponentIndex, &mut SnapshotWriter) + Send + Sync>;
+type ErasedLoadComponent = Box<dyn Fn(&mut World, Entity, &mut SnapshotReader) -> Result<(), SnapshotError> + Send + Sync>;
+type ErasedSaveSharedComponent = Box<dyn Fn(&SharedComponentValue, &mut SnapshotWriter) + Send + Sync>;
+type ErasedMapEntities = Box<dyn Fn(&mut World, Entity, &dyn Fn(Entity) -> Entity) + Send + Sync>;
+type ErasedParseComponent = Box<dyn Fn(&mut World, Entity, &SceneFields) -> Result<(), SceneErrorKind> + Send + Sync>;
+type ErasedDebugComponent = Box<dyn Fn(&[u8], &mut fmt::Formatter<'_>) -> fmt::Result + Send + Sync>;
+type ErasedCloneComponent = Box<dyn Fn(&[u8], &mut [u8]) + Send + Sync>;
+type ErasedSaveResource = Box<dyn Fn(&World, &mut SnapshotWriter) -> bool + Send + Sync>;
+type ErasedLoadResource = Box<dyn Fn(&mut World, &mut SnapshotReader) -> Result<(), SnapshotError> + Send + Sync>;
+type ErasedCloneResource = Box<dyn Fn(&World, &mut World) + Send + Sync>;
+type ErasedMapResourceEntities = Box<dyn Fn(&mut World, &dyn Fn(Entity) -> Entity) + Send + Sync>;
+
+enum ComponentCloner {
+    Copy,
+    Clone(ErasedCloneComponent)
+}
+
+pub struct ComponentRegistration {
+    pub component_type_id: ComponentTypeId,
+    pub key: &'static str,
+    save: Option<ErasedSaveComponent>,
+    load: Option<ErasedLoadComponent>,
+    save_shared: Option<ErasedSaveSharedComponent>,
+    load_shared: Option<ErasedLoadComponent>,
+    map_entities: Option<ErasedMapEntities>,
+    parse: Option<ErasedParseComponent>,
+    debug: Option<ErasedDebugComponent>,
+    clone: Option<ComponentCloner>,
+    despawn: Option<DespawnComponent>,
+    arrive: Option<ArriveComponent>,
+}
+
+pub struct ResourceRegistration {
+    pub type_id: TypeId,
+    pub key: &'static str,
+    save: ErasedSaveResource,
+    load: ErasedLoadResource,
+    clone: Option<ErasedCloneResource>,
+    map_entities: Option<ErasedMapResourceEntities>,
+    parse: Option<ParseResource>,
+}
+
+#[derive(Default)]
+pub struct ComponentRegistry {
+    components: Vec<ComponentRegistration>,
+    resources: Vec<ResourceRegistration>,
+}
+
+pub fn component_registry() -> ComponentRegistry {
+    ComponentRegistry::default()
+}
+
+pub fn register_component<C: Component>(registry: &mut ComponentRegistry, key: &'static str) {
+    let component_type_id = component_type_of::<C>();
+    gol_assert!(get_component_registration(registry, component_type_id).is_none());
+    gol_assert!(get_component_registration_by_key(registry, key).is_none());
+    registry.components.push(ComponentRegistration {
+        component_type_id,
+        key,
+        save: None,
+        load: None,
+        save_shared: None,
+        load_shared: None,
+        map_entities: None,
+        parse: None,
+        debug: None,
+        clone: None,
+        despawn: None,
+        arrive: None,
+    });
+}
+
+pub fn register_component_serialization<C: Component>(registry: &mut ComponentRegistry, save: SaveComponent<C>, load: LoadComponent<C>) {
+    let registration = get_component_registration_mut::<C>(registry);
+    registration.save = Some(Box::new(move |chunk, component_index, writer| {
+        save(C::fetch(chunk, component_index), writer)
+    }));
+    registration.load = Some(Box::new(move |world, entity, reader| {
+        add_component(world, entity, load(reader)?);
+        Ok(())
+    }));
+}
+
+pub fn register_shared_component_serialization<S: SharedComponent>(registry: &mut ComponentRegistry, save: SaveComponent<S>, load: LoadComponent<S>) {
+    let registration = get_component_registration_mut::<S>(registry);
+    registration.save_shared = Some(Box::new(move |value, writer| {
+        save(downcast_shared_value::<S>(value).unwrap(), writer)
+    }));
+    registration.load_shared = Some(Box::new(move |world, entity, reader| {
+        set_shared_component(world, entity, load(reader)?);
+        Ok(())
+    }));
+}
+
+pub fn register_component_entity_references<C: Component>(registry: &mut ComponentRegistry, map_entities: MapEntities<C>) {
+    let registration = get_component_registration_mut::<C>(registry);
+    registration.map_entities = Some(Box::new(move |world, entity, map| {
+        if let Some(component) = get_component_mut::<C>(world, entity) {
+            map_entities(component, map);
+        }
+    }));
+}
+
+pub fn register_component_scene_parser<C: Component>(registry: &mut ComponentRegistry, parse: ParseComponent<C>) {
+    let registration = get_component_registration_mut::<C>(registry);
+    registration.parse = Some(Box::new(move |world, entity, fields| {
+        add_component(world, entity, parse(fields)?);
+        Ok(())
+    }));
+}
+
+pub fn register_component_debug<C: Component + fmt::Debug>(registry: &mut ComponentRegistry) {
+    let registration = get_component_registration_mut::<C>(registry);
+    registration.debug = Some(Box::new(|bytes, f| {
+        gol_assert!(bytes.len() == size_of::<C>());
+        let component = ManuallyDrop::new(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const C) });
+        fmt::Debug::fmt(&*component, f)
+    }));
+}
+
+pub fn register_component_copy<C: Component + Copy>(registry: &mut ComponentRegistry) {
+    get_component_registration_mut::<C>(registry).clone = Some(ComponentCloner::Copy);
+}
+
+pub fn register_component_clone<C: Component + Clone>(registry: &mut ComponentRegistry) {
+    let registration = get_component_registration_mut::<C>(registry);
+    registration.clone = Some(ComponentCloner::Clone(Box::new(|source, target| {
+        if size_of::<C>() == 0 {
+            return;
+        }
+        for (source, target) in source.chunks_exact(size_of::<C>()).zip(target.chunks_exact_mut(size_of::<C>())) {
+            unsafe {
+                let component = ManuallyDrop::new(ptr::read_unaligned(source.as_ptr() as *const C));
+                ptr::write_unaligned(target.as_mut_ptr() as *mut C, (*component).clone());
+            }
+        }
+    })));
+}
+
+pub fn register_component_despawn<C: Component>(registry: &mut ComponentRegistry, despawn: DespawnComponent) {
+    get_component_registration_mut::<C>(registry).despawn = Some(despawn);
+}
+
+pub fn register_component_arrival<C: Component>(registry: &mut ComponentRegistry, arrive: ArriveComponent) {
+    get_component_registration_mut::<C>(registry).arrive = Some(arrive);
+}
+
+pub fn register_resource<R: Resource>(registry: &mut ComponentRegistry, key: &'static str, save: SaveResource<R>, load: LoadResource<R>) {
+    gol_assert!(get_resource_registration_by_key(registry, key).is_none());
+    registry.resources.push(ResourceRegistration {
+        type_id: TypeId::of::<R>(),
+        key,
+        save: Box::new(move |world, writer| {
+            if let Some(resource) = get_resource::<R>(world) {
+                save(resource, writer);
+                return true;
+            }
+            false
+        }),
+        load: Box::new(move |world, reader| {
+            add_resource(world, load(reader)?);
+            Ok(())
+        }),
+        clone: None,
+        map_entities: None,
+        parse: None,
+    });
+}
+
+pub fn register_resource_clone<R: Resource + Clone>(registry: &mut ComponentRegistry) {
+    let registration = get_resource_registration_mut::<R>(registry);
+    registration.clone = Some(Box::new(|source, target| {
+        if let Some(resource) = get_resource::<R>(source) {
+            add_resource(target, resource.clone());
+        }
+    }));
+}
+
+pub fn register_resource_entity_references<R: Resource>(registry: &mut ComponentRegistry, map: MapEntities<R>) {
+    let registration = get_resource_registration_mut::<R>(registry);
+    registration.map_entities = Some(Box::new(move |world, map_entity| {
+        if let Some(resource) = get_resource_mut::<R>(world) {
+            map(resource, map_entity);
+        }
+    }));
+}
+
+pub fn register_resource_scene_parser<R: Resource>(registry: &mut ComponentRegistry, parse: ParseResource) {
+    get_resource_registration_mut::<R>(registry).parse = Some(parse);
+}
+
+fn get_resource_registration_mut<R: Resource>(registry: &mut ComponentRegistry) -> &mut ResourceRegistration {
+    let type_id = TypeId::of::<R>();
+    gol_assert!(registry.resources.iter().any(|registration| registration.type_id == type_id));
+    registry.resources
+        .iter_mut()
+        .find(|registration| registration.type_id == type_id)
+        .unwrap()
+}
+
+fn get_component_registration_mut<C: Component>(registry: &mut ComponentRegistry) -> &mut ComponentRegistration {
+    let component_type_id = component_type_of::<C>();
+    gol_assert!(get_component_registration(registry, component_type_id).is_some());
+    registry.components
+        .iter_mut()
+        .find(|registration| registration.component_type_id == component_type_id)
+        .unwrap()
+}
+
+pub fn get_component_registration(registry: &ComponentRegistry, component_type_id: ComponentTypeId) -> Option<&ComponentRegistration> {
+    registry.components
+        .iter()
+        .find(|registration| registration.component_type_id == component_type_id)
+}
+
+pub fn get_component_registration_by_key<'a>(registry: &'a ComponentRegistry, key: &str) -> Option<&'a ComponentRegistration> {
+    registry.components
+        .iter()
+        .find(|registration| registration.key == key)
+}
+
+pub fn get_resource_registration_by_key<'a>(registry: &'a ComponentRegistry, key: &str) -> Option<&'a ResourceRegistration> {
+    registry.resources
+        .iter()
+        .find(|registration| registration.key == key)
+}
+
+pub fn resource_registration_iter(registry: &ComponentRegistry) -> std::slice::Iter<'_, ResourceRegistration> {
+    registry.resources.iter()
+}
+
+pub fn save_registered_component(registration: &ComponentRegistration, chunk: &ComponentChunk, component_index: ComponentIndex, writer: &mut SnapshotWriter) -> Result<(), SnapshotError> {
+    let save = registration.save
+        .as_ref()
+        .ok_or(SnapshotError::ComponentNotSerializable(registration.key))?;
+    save(chunk, component_index, writer);
+    Ok(())
+}
+
+pub fn load_registered_component(registration: &ComponentRegistration, world: &mut World, entity: Entity, reader: &mut SnapshotReader) -> Result<(), SnapshotError> {
+    let load = registration.load
+        .as_ref()
+        .ok_or(SnapshotError::ComponentNotSerializable(registration.key))?;
+    load(world, entity, reader)
+}
+
+pub fn save_registered_shared_component(registration: &ComponentRegistration, value: &SharedComponentValue, writer: &mut SnapshotWriter) -> Result<(), SnapshotError> {
+    let save = registration.save_shared
+        .as_ref()
+        .ok_or(SnapshotError::ComponentNotSerializable(registration.key))?;
+    save(value, writer);
+    Ok(())
+}
+
+pub fn load_registered_shared_component(registration: &ComponentRegistration, world: &mut World, entity: Entity, reader: &mut SnapshotReader) -> Result<(), SnapshotError> {
+    let load = registration.load_shared
+        .as_ref()
+        .ok_or(SnapshotError::ComponentNotSerializable(registration.key))?;
+    load(world, entity, reader)
+}
+
+pub fn map_registered_component_entities(registration: &ComponentRegistration, world: &mut World, entity: Entity, map: &dyn Fn(Entity) -> Entity) {
+    if let Some(map_entities) = registration.map_entities.as_ref() {
+        map_entities(world, entity, map);
+    }
+}
+
+pub fn despawn_registered_component(registration: &ComponentRegistration, world: &mut World, entity: Entity) {
+    if let Some(despawn) = registration.despawn {
+        despawn(world, entity);
+    }
+}
+
+pub fn arrive_registered_component(registration: &ComponentRegistration, world: &mut World, entity: Entity) {
+    if let Some(arrive) = registration.arrive {
+        arrive(world, entity);
+    }
+}
+
+pub fn save_registered_resource(registration: &ResourceRegistration, world: &World, writer: &mut SnapshotWriter) -> bool {
+    (registration.save)(world, writer)
+}
+
+pub fn load_registered_resource(registration: &ResourceRegistration, world: &mut World, reader: &mut SnapshotReader) -> Result<(), SnapshotError> {
+    (registration.load)(world, reader)
+}
+
+pub fn map_registered_resource_entities(registration: &ResourceRegistration, world: &mut World, map: &dyn Fn(Entity) -> Entity) {
+    if let Some(map_entities) = registration.map_entities.as_ref() {
+        map_entities(world, map);
+    }
+}
+
+pub fn is_registered_component_parseable(registration: &ComponentRegistration) -> bool {
+    registration.parse.is_some()
+}
+
+pub fn parse_registered_component(registration: &ComponentRegistration, world: &mut World, entity: Entity, fields: &SceneFields) -> Result<(), SceneErrorKind> {
+    let parse = registration.parse
+        .as_ref()
+        .ok_or_else(|| SceneErrorKind::ComponentNotParseable(registration.key.to_string()))?;
+    parse(world, entity, fields)
+}
+
+pub fn is_registered_resource_parseable(registration: &ResourceRegistration) -> bool {
+    registration.parse.is_some()
+}
+
+pub fn parse_registered_resource(registration: &ResourceRegistration, world: &mut World, fields: &SceneFields) -> Result<(), SceneErrorKind> {
+    let parse = registration.parse
+        .ok_or_else(|| SceneErrorKind::ResourceNotParseable(registration.key.to_string()))?;
+    parse(world, fields)
+}
+
+pub fn is_registered_component_debuggable(registration: &ComponentRegistration) -> bool {
+    registration.debug.is_some()
+}
+
+pub fn format_registered_component(registration: &ComponentRegistration, bytes: &[u8], f: &mut fmt::Formatter<'_>) -> Option<fmt::Result> {
+    registration.debug
+        .as_ref()
+        .map(|debug| debug(bytes, f))
+}
+
+pub fn is_registered_component_cloneable(registration: &ComponentRegistration) -> bool {
+    registration.clone.is_some()
+}
+
+pub fn clone_registered_component_stream(registration: &ComponentRegistration, source: &[u8], target: &mut [u8]) {
+    match registration.clone.as_ref() {
+        Some(ComponentCloner::Copy) => target.copy_from_slice(source),
+        Some(ComponentCloner::Clone(clone)) => clone(source, target),
+        None => panic!("component '{}' is not cloneable", registration.key)
+    }
+}
+
+pub fn clone_registered_resource(registration: &ResourceRegistration, source: &World, target: &mut World) {
+    if let Some(clone) = registration.clone.as_ref() {
+        clone(source, target);
+    }
+}
@@ -0,0 +1,45 @@
+use std::{
+    any::*,
+    collections::*
+};
+
+use super::*;
+
+pub trait Resource: 'static + Send + Sync {}
+
+impl<T: 'static + Send + Sync> Resource for T {}
+
+#[derive(Default)]
+pub struct Resources {
+    inner: HashMap<TypeId, Box<dyn Any + Send + Sync>>
+}
+
+pub fn resources() -> Resources {
+    Resources::default()
+}
+
+pub fn add_resource<R: Resource>(world: &mut World, resource: R) {
+    world.resources.inner.insert(TypeId::of::<R>(), Box::new(resource));
+}
+
+pub fn remove_resource<R: Resource>(world: &mut World) -> Option<R> {
+    world.resources.inner
+        .remove(&TypeId::of::<R>())
+        .map(|resource| *resource.downcast::<R>().unwrap())
+}
+
+pub fn has_resource<R: Resource>(world: &World) -> bool {
+    world.resources.inner.contains_key(&TypeId::of::<R>())
+}
+
+pub fn get_resource<R: Resource>(world: &World) -> Option<&R> {
+    world.resources.inner
+        .get(&TypeId::of::<R>())
+        .and_then(|resource| resource.downcast_ref::<R>())
+}
+
+pub fn get_resource_mut<R: Resource>(world: &mut World) -> Option<&mut R> {
+    world.resources.inner
+        .get_mut(&TypeId::of::<R>())
+        .and_then(|resource| resource.downcast_mut::<R>())
+}
@@ -0,0 +1,424 @@
+use std::{
+    cell::*,
+    collections::*,
+    fmt,
+    str::FromStr
+};
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SceneErrorKind {
+    ExpectedEntity,
+    InvalidEntityName(String),
+    DuplicateEntityName(String),
+    UnknownEntity(String),
+    UnknownComponent(String),
+    ComponentNotParseable(String),
+    DuplicateComponent(String),
+    MalformedField(String),
+    DuplicateField(String),
+    UnknownField(String),
+    MissingField(String),
+    InvalidValue { field: String, value: String, expected: &'static str },
+    UnknownResource(String),
+    ResourceNotParseable(String),
+    DuplicateResource(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SceneError {
+    pub line: usize,
+    pub kind: SceneErrorKind
+}
+
+fn scene_error(line: usize, kind: SceneErrorKind) -> SceneError {
+    SceneError { line, kind }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: ", self.line)?;
+        match &self.kind {
+            SceneErrorKind::ExpectedEntity => write!(f, "components must follow an 'entity' line"),
+            SceneErrorKind::InvalidEntityName(name) => write!(f, "'{}' is not a valid entity name", name),
+            SceneErrorKind::DuplicateEntityName(name) => write!(f, "entity '{}' is declared more than once", name),
+            SceneErrorKind::UnknownEntity(name) => write!(f, "no entity named '{}'", name),
+            SceneErrorKind::UnknownComponent(key) => write!(f, "unknown component '{}'", key),
+            SceneErrorKind::ComponentNotParseable(key) => write!(f, "component '{}' cannot be read from a scene", key),
+            SceneErrorKind::DuplicateComponent(key) => write!(f, "component '{}' appears twice on the same entity", key),
+            SceneErrorKind::MalformedField(field) => write!(f, "expected 'name=value' but found '{}'", field),
+            SceneErrorKind::DuplicateField(field) => write!(f, "field '{}' is given more than once", field),
+            SceneErrorKind::UnknownField(field) => write!(f, "unknown field '{}'", field),
+            SceneErrorKind::MissingField(field) => write!(f, "missing field '{}'", field),
+            SceneErrorKind::InvalidValue { field, value, expected } => write!(f, "field '{}' expects {} but found '{}'", field, expected, value),
+            SceneErrorKind::UnknownResource(key) => write!(f, "unknown resource '{}'", key),
+            SceneErrorKind::ResourceNotParseable(key) => write!(f, "resource '{}' cannot be read from a scene", key),
+            SceneErrorKind::DuplicateResource(key) => write!(f, "resource '{}' is given more than once", key),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SceneEntities {
+    names: HashMap<String, Entity>
+}
+
+pub fn get_scene_entity(entities: &SceneEntities, name: &str) -> Option<Entity> {
+    entities.names.get(name).copied()
+}
+
+pub trait SceneValue: Sized {
+    const EXPECTED: &'static str;
+    fn parse_scene_value(text: &str, entities: &SceneEntities) -> Result<Self, SceneValueError>;
+}
+
+pub enum SceneValueError {
+    Invalid,
+    UnknownEntity,
+}
+
+fn parse_from_str<T: FromStr>(text: &str) -> Result<T, SceneValueError> {
+    text.parse().map_err(|_| SceneValueError::Invalid)
+}
+
+impl SceneValue for f32 {
+    const EXPECTED: &'static str = "a number";
+    fn parse_scene_value(text: &str, _entities: &SceneEntities) -> Result<Self, SceneValueError> {
+        parse_from_str(text)
+    }
+}
+
+impl SceneValue for i32 {
+    const EXPECTED: &'static str = "an integer";
+    fn parse_scene_value(text: &str, _entities: &SceneEntities) -> Result<Self, SceneValueError> {
+        parse_from_str(text)
+    }
+}
+
+impl SceneValue for u32 {
+    const EXPECTED: &'static str = "a positive integer";
+    fn parse_scene_value(text: &str, _entities: &SceneEntities) -> Result<Self, SceneValueError> {
+        parse_from_str(text)
+    }
+}
+
+impl SceneValue for bool {
+    const EXPECTED: &'static str = "true or false";
+    fn parse_scene_value(text: &str, _entities: &SceneEntities) -> Result<Self, SceneValueError> {
+        parse_from_str(text)
+    }
+}
+
+impl SceneValue for String {
+    const EXPECTED: &'static str = "text";
+    fn parse_scene_value(text: &str, _entities: &SceneEntities) -> Result<Self, SceneValueError> {
+        Ok(text.to_string())
+    }
+}
+
+impl SceneValue for Entity {
+    const EXPECTED: &'static str = "an entity name";
+    fn parse_scene_value(text: &str, entities: &SceneEntities) -> Result<Self, SceneValueError> {
+        if !is_valid_entity_name(text) {
+            return Err(SceneValueError::Invalid);
+        }
+        get_scene_entity(entities, text).ok_or(SceneValueError::UnknownEntity)
+    }
+}
+
+impl SceneValue for Option<Entity> {
+    const EXPECTED: &'static str = "an entity name or none";
+    fn parse_scene_value(text: &str, entities: &SceneEntities) -> Result<Self, SceneValueError> {
+        if text == "none" {
+            return Ok(None);
+        }
+        Entity::parse_scene_value(text, entities).map(Some)
+    }
+}
+
+pub struct SceneFields<'a> {
+    fields: Vec<(&'a str, &'a str)>,
+    used: RefCell<Vec<bool>>,
+    entities: &'a SceneEntities,
+}
+
+impl<'a> SceneFields<'a> {
+    fn find(&self, name: &str) -> Option<&'a str> {
+        let index = self.fields.iter().position(|(field, _)| *field == name)?;
+        self.used.borrow_mut()[index] = true;
+        Some(self.fields[index].1)
+    }
+
+    fn parse<T: SceneValue>(&self, name: &str, value: &str) -> Result<T, SceneErrorKind> {
+        T::parse_scene_value(value, self.entities).map_err(|error| match error {
+            SceneValueError::UnknownEntity => SceneErrorKind::UnknownEntity(value.to_string()),
+            SceneValueError::Invalid => SceneErrorKind::InvalidValue { field: name.to_string(), value: value.to_string(), expected: T::EXPECTED },
+        })
+    }
+
+    pub fn get<T: SceneValue>(&self, name: &str) -> Result<T, SceneErrorKind> {
+        let value = self.find(name).ok_or_else(|| SceneErrorKind::MissingField(name.to_string()))?;
+        self.parse(name, value)
+    }
+
+    pub fn get_or<T: SceneValue>(&self, name: &str, default: T) -> Result<T, SceneErrorKind> {
+        match self.find(name) {
+            Some(value) => self.parse(name, value),
+            None => Ok(default),
+        }
+    }
+
+    fn first_unused(&self) -> Option<&'a str> {
+        self.used.borrow()
+            .iter()
+            .position(|used| !used)
+            .map(|index| self.fields[index].0)
+    }
+}
+
+struct SceneEntityDeclaration<'a> {
+    name: Option<&'a str>,
+    components: Vec<SceneComponentDeclaration<'a>>,
+}
+
+struct SceneComponentDeclaration<'a> {
+    line: usize,
+    key: &'a str,
+    fields: Vec<(&'a str, &'a str)>,
+}
+
+#[derive(Default)]
+struct SceneDeclarations<'a> {
+    entities: Vec<SceneEntityDeclaration<'a>>,
+    resources: Vec<SceneComponentDeclaration<'a>>,
+}
+
+fn is_valid_entity_name(name: &str) -> bool {
+    let mut characters = name.chars();
+    matches!(characters.next(), Some(first) if first.is_ascii_alphabetic() || first == '_')
+        && characters.all(|character| character.is_ascii_alphanumeric() || character == '_')
+        && name != "none"
+}
+
+fn parse_scene_fields<'a>(tokens: impl Iterator<Item = &'a str>, line_number: usize) -> Result<Vec<(&'a str, &'a str)>, SceneError> {
+    let mut fields = Vec::<(&str, &str)>::default();
+    for token in tokens {
+        let (name, value) = token
+            .split_once('=')
+            .filter(|(name, value)| !name.is_empty() && !value.is_empty())
+            .ok_or_else(|| scene_error(line_number, SceneErrorKind::MalformedField(token.to_string())))?;
+        if fields.iter().any(|(existing, _)| *existing == name) {
+            return Err(scene_error(line_number, SceneErrorKind::DuplicateField(name.to_string())));
+        }
+        fields.push((name, value));
+    }
+    Ok(fields)
+}
+
+fn parse_scene_declarations(text: &str) -> Result<SceneDeclarations<'_>, SceneError> {
+    let mut declarations = SceneDeclarations::default();
+    let mut in_entity = false;
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut tokens = line.split_whitespace();
+        let first = tokens.next().unwrap();
+
+        if first == "entity" {
+            let name = tokens.next();
+            if let Some(name) = name {
+                if !is_valid_entity_name(name) {
+                    return Err(scene_error(line_number, SceneErrorKind::InvalidEntityName(name.to_string())));
+                }
+                if declarations.entities.iter().any(|declaration| declaration.name == Some(name)) {
+                    return Err(scene_error(line_number, SceneErrorKind::DuplicateEntityName(name.to_string())));
+                }
+            }
+            if let Some(extra) = tokens.next() {
+                return Err(scene_error(line_number, SceneErrorKind::InvalidEntityName(extra.to_string())));
+            }
+            declarations.entities.push(SceneEntityDeclaration { name, components: vec!() });
+            in_entity = true;
+            continue;
+        }
+
+        if first == "resource" {
+            let key = tokens.next().ok_or(scene_error(line_number, SceneErrorKind::UnknownResource(String::default())))?;
+            if declarations.resources.iter().any(|resource| resource.key == key) {
+                return Err(scene_error(line_number, SceneErrorKind::DuplicateResource(key.to_string())));
+            }
+            let fields = parse_scene_fields(tokens, line_number)?;
+            declarations.resources.push(SceneComponentDeclaration { line: line_number, key, fields });
+            in_entity = false;
+            continue;
+        }
+
+        let entity = declarations.entities
+            .last_mut()
+            .filter(|_| in_entity)
+            .ok_or(scene_error(line_number, SceneErrorKind::ExpectedEntity))?;
+
+        if entity.components.iter().any(|component| component.key == first) {
+            return Err(scene_error(line_number, SceneErrorKind::DuplicateComponent(first.to_string())));
+        }
+
+        let fields = parse_scene_fields(tokens, line_number)?;
+        entity.components.push(SceneComponentDeclaration { line: line_number, key: first, fields });
+    }
+
+    Ok(declarations)
+}
+
+fn scene_fields<'a>(declaration: &SceneComponentDeclaration<'a>, entities: &'a SceneEntities) -> SceneFields<'a> {
+    SceneFields {
+        used: RefCell::new(vec![false; declaration.fields.len()]),
+        fields: declaration.fields.clone(),
+        entities,
+    }
+}
+
+fn expect_all_fields_used(fields: &SceneFields, line: usize) -> Result<(), SceneError> {
+    match fields.first_unused() {
+        Some(unknown) => Err(scene_error(line, SceneErrorKind::UnknownField(unknown.to_string()))),
+        None => Ok(()),
+    }
+}
+
+pub fn load_scene(world: &mut World, text: &str) -> Result<SceneEntities, SceneError> {
+    let declarations = parse_scene_declarations(text)?;
+    let registry = get_registry(world).clone();
+
+    for resource in &declarations.resources {
+        let registration = get_resource_registration_by_key(&registry, resource.key)
+            .ok_or_else(|| scene_error(resource.line, SceneErrorKind::UnknownResource(resource.key.to_string())))?;
+        if !is_registered_resource_parseable(registration) {
+            return Err(scene_error(resource.line, SceneErrorKind::ResourceNotParseable(resource.key.to_string())));
+        }
+    }
+    for declaration in &declarations.entities {
+        for component in &declaration.components {
+            let registration = get_component_registration_by_key(&registry, component.key)
+                .ok_or_else(|| scene_error(component.line, SceneErrorKind::UnknownComponent(component.key.to_string())))?;
+            if !is_registered_component_parseable(registration) {
+                return Err(scene_error(component.line, SceneErrorKind::ComponentNotParseable(component.key.to_string())));
+            }
+        }
+    }
+
+    let mut entities = SceneEntities::default();
+    let mut created = Vec::<Entity>::default();
+    for declaration in &declarations.entities {
+        let entity = add_entity(world);
+        if let Some(name) = declaration.name {
+            entities.names.insert(name.to_string(), entity);
+        }
+        created.push(entity);
+    }
+
+    for resource in &declarations.resources {
+        let registration = get_resource_registration_by_key(&registry, resource.key).unwrap();
+        let fields = scene_fields(resource, &entities);
+        parse_registered_resource(registration, world, &fields)
+            .map_err(|kind| scene_error(resource.line, kind))?;
+        expect_all_fields_used(&fields, resource.line)?;
+    }
+
+    for (declaration, entity) in declarations.entities.iter().zip(created) {
+        for component in &declaration.components {
+            let registration = get_component_registration_by_key(&registry, component.key).unwrap();
+            let fields = scene_fields(component, &entities);
+            parse_registered_component(registration, world, entity, &fields)
+                .map_err(|kind| scene_error(component.line, kind))?;
+            expect_all_fields_used(&fields, component.line)?;
+        }
+    }
+
+    Ok(entities)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(f32, f32);
+
+    #[derive(Debug, PartialEq)]
+    struct Link {
+        target: Option<Entity>,
+        strength: i32
+    }
+
+    fn parse_position(fields: &SceneFields) -> Result<Position, SceneErrorKind> {
+        Ok(Position(fields.get("x")?, fields.get("y")?))
+    }
+
+    fn parse_link(fields: &SceneFields) -> Result<Link, SceneErrorKind> {
+        Ok(Link { target: fields.get_or("target", None)?, strength: fields.get_or("strength", 1)? })
+    }
+
+    fn test_world() -> World {
+        let mut registry = component_registry();
+        register_component::<Position>(&mut registry, "Position");
+        register_component_scene_parser(&mut registry, parse_position);
+        register_component::<Link>(&mut registry, "Link");
+        register_component_scene_parser(&mut registry, parse_link);
+        create_world_with_registry(Arc::new(registry))
+    }
+
+    fn load_error(text: &str) -> SceneError {
+        load_scene(&mut test_world(), text).err().unwrap()
+    }
+
+    #[test]
+    fn loads_entities_and_resolves_names() {
+        let mut world = test_world();
+        let scene = "
+            # two linked cells
+            entity a
+                Position x=0 y=1.5
+                Link target=b
+            entity b
+                Position x=2 y=-1   # trailing comment
+                Link target=none strength=3
+            entity
+                Position x=9 y=9
+        ";
+        let entities = load_scene(&mut world, scene).unwrap();
+        let a = get_scene_entity(&entities, "a").unwrap();
+        let b = get_scene_entity(&entities, "b").unwrap();
+
+        assert_eq!(get_component::<Position>(&world, a), Some(&Position(0.0, 1.5)));
+        assert_eq!(get_component::<Link>(&world, a), Some(&Link { target: Some(b), strength: 1 }));
+        assert_eq!(get_component::<Position>(&world, b), Some(&Position(2.0, -1.0)));
+        assert_eq!(get_component::<Link>(&world, b), Some(&Link { target: None, strength: 3 }));
+        assert_eq!(world_stats(&world).entity_count, 3);
+    }
+
+    #[test]
+    fn reports_precise_errors() {
+        assert_eq!(load_error("Position x=0 y=0"), scene_error(1, SceneErrorKind::ExpectedEntity));
+        assert_eq!(load_error("entity a\nentity a"), scene_error(2, SceneErrorKind::DuplicateEntityName("a".to_string())));
+        assert_eq!(load_error("entity 1a"), scene_error(1, SceneErrorKind::InvalidEntityName("1a".to_string())));
+        assert_eq!(load_error("entity a\n  Velocity x=1"), scene_error(2, SceneErrorKind::UnknownComponent("Velocity".to_string())));
+        assert_eq!(load_error("entity a\n  Position x=1"), scene_error(2, SceneErrorKind::MissingField("y".to_string())));
+        assert_eq!(load_error("entity a\n  Position x=1 y=2 z=3"), scene_error(2, SceneErrorKind::UnknownField("z".to_string())));
+        assert_eq!(load_error("entity a\n  Position x=1 x=2"), scene_error(2, SceneErrorKind::DuplicateField("x".to_string())));
+        assert_eq!(load_error("entity a\n  Position x1"), scene_error(2, SceneErrorKind::MalformedField("x1".to_string())));
+        assert_eq!(load_error("resource Rule rule=B3/S23"), scene_error(1, SceneErrorKind::UnknownResource("Rule".to_string())));
+        assert_eq!(load_error("entity a\nresource Rule\n  Position x=1 y=2"), scene_error(3, SceneErrorKind::ExpectedEntity));
+        assert_eq!(load_error("entity a\n  Link target=c"), scene_error(2, SceneErrorKind::UnknownEntity("c".to_string())));
+        assert_eq!(
+            load_error("entity a\n  Position x=one y=2"),
+            scene_error(2, SceneErrorKind::InvalidValue { field: "x".to_string(), value: "one".to_string(), expected: "a number" })
+        );
+    }
+}
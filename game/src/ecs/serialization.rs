@@ -0,0 +1,550 @@
+use std::{
+    collections::*,
+    ffi::*,
+    fmt,
+    slice,
+    sync::Arc
+};
+
+use gol_engine::*;
+
+use super::*;
+
+pub const SNAPSHOT_MAGIC: [u8; 4] = *b"GOLW";
+pub const SNAPSHOT_VERSION: u32 = 2;
+const MINIMUM_SNAPSHOT_VERSION: u32 = 1;
+const SAVED_ENTITY_LOCATION_BYTES: usize = 8 + 4 + 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    UnexpectedEnd { position: usize },
+    InvalidMagic,
+    UnsupportedVersion(u32),
+    UnknownComponent(String),
+    UnknownResource(String),
+    ComponentNotRegistered(&'static str),
+    ComponentNotSerializable(&'static str),
+    UnknownEntity(usize),
+    PayloadLengthMismatch { key: String, expected: usize, read: usize },
+    InvalidValue(&'static str),
+    ReadFailed(String),
+    WriteFailed(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::UnexpectedEnd { position } => write!(f, "unexpected end of snapshot at byte {}", position),
+            SnapshotError::InvalidMagic => write!(f, "not a world snapshot"),
+            SnapshotError::UnsupportedVersion(version) => write!(f, "unsupported snapshot version {} (expected {})", version, SNAPSHOT_VERSION),
+            SnapshotError::UnknownComponent(key) => write!(f, "snapshot contains unregistered component '{}'", key),
+            SnapshotError::UnknownResource(key) => write!(f, "snapshot contains unregistered resource '{}'", key),
+            SnapshotError::ComponentNotRegistered(name) => write!(f, "component {} is not registered", name),
+            SnapshotError::ComponentNotSerializable(key) => write!(f, "component '{}' has no save or load function", key),
+            SnapshotError::UnknownEntity(index) => write!(f, "reference to entity {} which is not in the snapshot", index),
+            SnapshotError::PayloadLengthMismatch { key, expected, read } => write!(f, "'{}' payload is {} bytes but {} were read", key, expected, read),
+            SnapshotError::InvalidValue(what) => write!(f, "invalid {} in snapshot", what),
+            SnapshotError::ReadFailed(file_name) => write!(f, "could not read '{}'", file_name),
+            SnapshotError::WriteFailed(file_name) => write!(f, "could not write '{}'", file_name),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct SnapshotWriter {
+    bytes: Vec<u8>
+}
+
+impl SnapshotWriter {
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_u64(&mut self, value: u64) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_bytes(&value.to_le_bytes());
+    }
+
+    pub fn write_str(&mut self, value: &str) {
+        self.write_u32(value.len() as u32);
+        self.write_bytes(value.as_bytes());
+    }
+
+    pub fn write_entity(&mut self, entity: Entity) {
+        self.write_u64(entity_index(entity) as u64);
+    }
+
+    pub fn write_optional_entity(&mut self, entity: Option<Entity>) {
+        self.write_bool(entity.is_some());
+        if let Some(entity) = entity {
+            self.write_entity(entity);
+        }
+    }
+
+    fn begin_length_prefixed(&mut self) -> usize {
+        self.write_u32(0);
+        self.bytes.len()
+    }
+
+    fn end_length_prefixed(&mut self, start: usize) {
+        let length = (self.bytes.len() - start) as u32;
+        self.bytes[start - 4..start].copy_from_slice(&length.to_le_bytes());
+    }
+}
+
+#[derive(Clone)]
+pub struct SnapshotReader<'a> {
+    bytes: &'a [u8],
+    position: usize
+}
+
+pub fn snapshot_reader(bytes: &[u8]) -> SnapshotReader<'_> {
+    SnapshotReader { bytes, position: 0 }
+}
+
+impl<'a> SnapshotReader<'a> {
+    pub fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], SnapshotError> {
+        if self.bytes.len() - self.position < count {
+            return Err(SnapshotError::UnexpectedEnd { position: self.position });
+        }
+        let bytes = &self.bytes[self.position..self.position + count];
+        self.position += count;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], SnapshotError> {
+        Ok(self.read_bytes(N)?.try_into().unwrap())
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, SnapshotError> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, SnapshotError> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(SnapshotError::InvalidValue("bool")),
+        }
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, SnapshotError> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, SnapshotError> {
+        Ok(i32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, SnapshotError> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_f32(&mut self) -> Result<f32, SnapshotError> {
+        Ok(f32::from_le_bytes(self.read_array()?))
+    }
+
+    pub fn read_str(&mut self) -> Result<&'a str, SnapshotError> {
+        let length = self.read_u32()? as usize;
+        std::str::from_utf8(self.read_bytes(length)?).map_err(|_| SnapshotError::InvalidValue("string"))
+    }
+
+    pub fn read_entity(&mut self) -> Result<Entity, SnapshotError> {
+        Ok(entity(self.read_u64()? as usize))
+    }
+
+    pub fn read_optional_entity(&mut self) -> Result<Option<Entity>, SnapshotError> {
+        if self.read_bool()? {
+            return Ok(Some(self.read_entity()?));
+        }
+        Ok(None)
+    }
+
+    fn read_length_prefixed(&mut self) -> Result<SnapshotReader<'a>, SnapshotError> {
+        let length = self.read_u32()? as usize;
+        Ok(snapshot_reader(self.read_bytes(length)?))
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.position
+    }
+
+    fn is_at_end(&self) -> bool {
+        self.position == self.bytes.len()
+    }
+}
+
+fn expect_fully_read(reader: &SnapshotReader, key: &str) -> Result<(), SnapshotError> {
+    if !reader.is_at_end() {
+        return Err(SnapshotError::PayloadLengthMismatch { key: key.to_string(), expected: reader.bytes.len(), read: reader.position });
+    }
+    Ok(())
+}
+
+pub fn save_world(world: &World) -> Result<Vec<u8>, SnapshotError> {
+    let registry = get_registry(world);
+    let archetypes: Vec<&Archetype> = archetype_iter(&world.archetypes)
+        .filter(|archetype| archetype_entity_count(archetype) > 0)
+        .collect();
+
+    let mut registrations = Vec::<&ComponentRegistration>::default();
+    let mut layouts = Vec::<Vec<u32>>::default();
+    let mut shared_layouts = Vec::<Vec<u32>>::default();
+    for archetype in &archetypes {
+        let layout = copy_layout_components(&archetype.layout)
+            .map(|component_type_id| get_key_index(registry, &mut registrations, component_type_id))
+            .collect::<Result<Vec<_>, _>>()?;
+        let shared_layout = layout_shared_component_types(&archetype.layout)
+            .map(|component_type_id| get_key_index(registry, &mut registrations, component_type_id))
+            .collect::<Result<Vec<_>, _>>()?;
+        layouts.push(layout);
+        shared_layouts.push(shared_layout);
+    }
+
+    let mut writer = SnapshotWriter::default();
+    writer.write_bytes(&SNAPSHOT_MAGIC);
+    writer.write_u32(SNAPSHOT_VERSION);
+
+    writer.write_u32(registrations.len() as u32);
+    for registration in &registrations {
+        writer.write_str(registration.key);
+    }
+
+    writer.write_u32(archetypes.len() as u32);
+    for ((archetype, layout), shared_layout) in archetypes.iter().zip(&layouts).zip(&shared_layouts) {
+        writer.write_u32(layout.len() as u32);
+        for key_index in layout {
+            writer.write_u32(*key_index);
+        }
+        writer.write_u32(shared_layout.len() as u32);
+        for (key_index, value) in shared_layout.iter().zip(&archetype.layout.shared) {
+            writer.write_u32(*key_index);
+            let start = writer.begin_length_prefixed();
+            save_registered_shared_component(registrations[*key_index as usize], value, &mut writer)?;
+            writer.end_length_prefixed(start);
+        }
+        writer.write_u64(archetype_entity_count(archetype) as u64);
+    }
+
+    writer.write_u64(archetypes.iter().map(|archetype| archetype_entity_count(archetype)).sum::<usize>() as u64);
+    for (archetype_number, archetype) in archetypes.iter().enumerate() {
+        for (row, (chunk, component_index)) in archetype.chunks.component_iter().enumerate() {
+            writer.write_entity(get_entity_in_chunk(chunk, component_index));
+            writer.write_u32(archetype_number as u32);
+            writer.write_u64(row as u64);
+        }
+    }
+
+    for (archetype, layout) in archetypes.iter().zip(&layouts) {
+        for (chunk, component_index) in archetype.chunks.component_iter() {
+            for key_index in layout {
+                let start = writer.begin_length_prefixed();
+                save_registered_component(registrations[*key_index as usize], chunk, component_index, &mut writer)?;
+                writer.end_length_prefixed(start);
+            }
+        }
+    }
+
+    let resource_count_start = writer.begin_length_prefixed();
+    let mut resource_count = 0u32;
+    for registration in resource_registration_iter(registry) {
+        let mut resource_writer = SnapshotWriter::default();
+        if save_registered_resource(registration, world, &mut resource_writer) {
+            writer.write_str(registration.key);
+            writer.write_u32(resource_writer.bytes.len() as u32);
+            writer.write_bytes(&resource_writer.bytes);
+            resource_count += 1;
+        }
+    }
+    writer.bytes[resource_count_start - 4..resource_count_start].copy_from_slice(&resource_count.to_le_bytes());
+
+    Ok(writer.bytes)
+}
+
+fn get_key_index<'a>(registry: &'a ComponentRegistry, registrations: &mut Vec<&'a ComponentRegistration>, component_type_id: ComponentTypeId) -> Result<u32, SnapshotError> {
+    if let Some(key_index) = registrations.iter().position(|known| known.component_type_id == component_type_id) {
+        return Ok(key_index as u32);
+    }
+    let registration = get_component_registration(registry, component_type_id)
+        .ok_or(SnapshotError::ComponentNotRegistered(component_type_name(component_type_id)))?;
+    registrations.push(registration);
+    Ok((registrations.len() - 1) as u32)
+}
+
+pub fn load_world(bytes: &[u8], registry: Arc<ComponentRegistry>) -> Result<World, SnapshotError> {
+    let mut world = create_world_with_registry(registry.clone());
+    let mut reader = snapshot_reader(bytes);
+
+    if reader.read_bytes(SNAPSHOT_MAGIC.len()).map_err(|_| SnapshotError::InvalidMagic)? != SNAPSHOT_MAGIC {
+        return Err(SnapshotError::InvalidMagic);
+    }
+    let version = reader.read_u32()?;
+    if !(MINIMUM_SNAPSHOT_VERSION..=SNAPSHOT_VERSION).contains(&version) {
+        return Err(SnapshotError::UnsupportedVersion(version));
+    }
+
+    let key_count = reader.read_u32()?;
+    let mut registrations = Vec::<&ComponentRegistration>::default();
+    for _ in 0..key_count {
+        let key = reader.read_str()?;
+        let registration = get_component_registration_by_key(&registry, key)
+            .ok_or_else(|| SnapshotError::UnknownComponent(key.to_string()))?;
+        registrations.push(registration);
+    }
+
+    let archetype_count = reader.read_u32()?;
+    let mut layouts = Vec::<Vec<&ComponentRegistration>>::default();
+    let mut shared_layouts = Vec::<Vec<(&ComponentRegistration, SnapshotReader)>>::default();
+    let mut rows = Vec::<Vec<Option<Entity>>>::default();
+    let mut total_rows = 0usize;
+    for _ in 0..archetype_count {
+        let component_count = reader.read_u32()?;
+        let mut layout = Vec::<&ComponentRegistration>::default();
+        for _ in 0..component_count {
+            let key_index = reader.read_u32()? as usize;
+            layout.push(*registrations.get(key_index).ok_or(SnapshotError::InvalidValue("component key index"))?);
+        }
+        layouts.push(layout);
+        let mut shared_layout = Vec::<(&ComponentRegistration, SnapshotReader)>::default();
+        let shared_count = if version >= 2 { reader.read_u32()? } else { 0 };
+        for _ in 0..shared_count {
+            let key_index = reader.read_u32()? as usize;
+            let registration = *registrations.get(key_index).ok_or(SnapshotError::InvalidValue("component key index"))?;
+            shared_layout.push((registration, reader.read_length_prefixed()?));
+        }
+        shared_layouts.push(shared_layout);
+        let row_count = reader.read_u64()? as usize;
+        total_rows = total_rows
+            .checked_add(row_count)
+            .filter(|total_rows| *total_rows <= reader.remaining() / SAVED_ENTITY_LOCATION_BYTES)
+            .ok_or(SnapshotError::InvalidValue("row count"))?;
+        rows.push(vec![None; row_count]);
+    }
+
+    let entity_count = reader.read_u64()?;
+    let mut entity_map = HashMap::<Entity, Entity>::default();
+    for _ in 0..entity_count {
+        let saved_entity = reader.read_entity()?;
+        let archetype_number = reader.read_u32()? as usize;
+        let row = reader.read_u64()? as usize;
+        let slot = rows
+            .get_mut(archetype_number)
+            .and_then(|archetype_rows| archetype_rows.get_mut(row))
+            .ok_or(SnapshotError::InvalidValue("entity location"))?;
+        if slot.is_some() || entity_map.contains_key(&saved_entity) {
+            return Err(SnapshotError::InvalidValue("entity location"));
+        }
+        let loaded_entity = add_entity(&mut world);
+        entity_map.insert(saved_entity, loaded_entity);
+        *slot = Some(loaded_entity);
+    }
+
+    for ((layout, shared_layout), archetype_rows) in layouts.iter().zip(&shared_layouts).zip(&rows) {
+        for loaded_entity in archetype_rows {
+            let loaded_entity = loaded_entity.ok_or(SnapshotError::InvalidValue("entity location"))?;
+            for (registration, shared_reader) in shared_layout {
+                let mut shared_reader = shared_reader.clone();
+                load_registered_shared_component(registration, &mut world, loaded_entity, &mut shared_reader)?;
+                expect_fully_read(&shared_reader, registration.key)?;
+            }
+            for registration in layout {
+                let mut component_reader = reader.read_length_prefixed()?;
+                load_registered_component(registration, &mut world, loaded_entity, &mut component_reader)?;
+                expect_fully_read(&component_reader, registration.key)?;
+            }
+        }
+    }
+
+    let unknown_entity = std::cell::Cell::new(None);
+    let map = |saved_entity: Entity| {
+        match entity_map.get(&saved_entity) {
+            Some(loaded_entity) => *loaded_entity,
+            None => {
+                unknown_entity.set(Some(saved_entity));
+                saved_entity
+            }
+        }
+    };
+    for (layout, archetype_rows) in layouts.iter().zip(&rows) {
+        for loaded_entity in archetype_rows.iter().flatten() {
+            for registration in layout {
+                map_registered_component_entities(registration, &mut world, *loaded_entity, &map);
+            }
+        }
+    }
+
+    let resource_count = reader.read_u32()?;
+    for _ in 0..resource_count {
+        let key = reader.read_str()?;
+        let registration = get_resource_registration_by_key(&registry, key)
+            .ok_or_else(|| SnapshotError::UnknownResource(key.to_string()))?;
+        let mut resource_reader = reader.read_length_prefixed()?;
+        load_registered_resource(registration, &mut world, &mut resource_reader)?;
+        expect_fully_read(&resource_reader, key)?;
+        map_registered_resource_entities(registration, &mut world, &map);
+    }
+    if let Some(saved_entity) = unknown_entity.get() {
+        return Err(SnapshotError::UnknownEntity(entity_index(saved_entity)));
+    }
+
+    if !reader.is_at_end() {
+        return Err(SnapshotError::InvalidValue("trailing data"));
+    }
+
+    Ok(world)
+}
+
+pub fn save_world_to_file(world: &World, game_memory: &GameMemory, thread_context: &mut ThreadContext, file_name: &str) -> Result<(), SnapshotError> {
+    let mut bytes = save_world(world)?;
+    let size = safe_truncate_u64(bytes.len() as u64);
+    if !game_memory.debug_platform_write_entire_file(thread_context, file_name, size, bytes.as_mut_ptr() as *mut c_void) {
+        return Err(SnapshotError::WriteFailed(file_name.to_string()));
+    }
+    Ok(())
+}
+
+pub fn load_world_from_file(game_memory: &GameMemory, thread_context: &mut ThreadContext, file_name: &str, registry: Arc<ComponentRegistry>) -> Result<World, SnapshotError> {
+    let file = game_memory
+        .debug_platform_read_entire_file(thread_context, file_name)
+        .ok_or_else(|| SnapshotError::ReadFailed(file_name.to_string()))?;
+    let bytes = unsafe { slice::from_raw_parts(file.contents as *const u8, file.contents_size as usize) };
+    let world = load_world(bytes, registry);
+    game_memory.debug_platform_free_file_memory(thread_context, file.contents);
+    world
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    struct Position(i32, i32);
+
+    #[derive(Debug, PartialEq)]
+    struct Link(Option<Entity>);
+
+    #[derive(Debug, PartialEq)]
+    struct Generation(u64);
+
+    fn save_position(position: &Position, writer: &mut SnapshotWriter) {
+        writer.write_i32(position.0);
+        writer.write_i32(position.1);
+    }
+
+    fn load_position(reader: &mut SnapshotReader) -> Result<Position, SnapshotError> {
+        Ok(Position(reader.read_i32()?, reader.read_i32()?))
+    }
+
+    fn save_link(link: &Link, writer: &mut SnapshotWriter) {
+        writer.write_optional_entity(link.0);
+    }
+
+    fn load_link(reader: &mut SnapshotReader) -> Result<Link, SnapshotError> {
+        Ok(Link(reader.read_optional_entity()?))
+    }
+
+    fn map_link(link: &mut Link, map: &dyn Fn(Entity) -> Entity) {
+        link.0 = link.0.map(map);
+    }
+
+    fn test_registry() -> Arc<ComponentRegistry> {
+        let mut registry = component_registry();
+        register_component::<Position>(&mut registry, "position");
+        register_component_serialization(&mut registry, save_position, load_position);
+        register_component::<Link>(&mut registry, "link");
+        register_component_serialization(&mut registry, save_link, load_link);
+        register_component_entity_references(&mut registry, map_link);
+        register_resource::<Generation>(&mut registry, "generation", |generation, writer| writer.write_u64(generation.0), |reader| Ok(Generation(reader.read_u64()?)));
+        Arc::new(registry)
+    }
+
+    #[test]
+    fn round_trip_remaps_entities() {
+        let registry = test_registry();
+        let mut world = create_world_with_registry(registry.clone());
+        let _unlocated = add_entity(&mut world);
+        let a = add_entity(&mut world);
+        let b = add_entity(&mut world);
+        add_component(&mut world, a, Position(1, 2));
+        add_component(&mut world, b, Position(3, 4));
+        add_component(&mut world, b, Link(Some(a)));
+        add_component(&mut world, a, Link(Some(b)));
+        add_resource(&mut world, Generation(42));
+
+        let bytes = save_world(&world).unwrap();
+        let loaded = load_world(&bytes, registry).unwrap();
+
+        let loaded_entities: Vec<Entity> = (0..2).map(entity).collect();
+        let loaded_a = *loaded_entities.iter().find(|e| get_component::<Position>(&loaded, **e) == Some(&Position(1, 2))).unwrap();
+        let loaded_b = *loaded_entities.iter().find(|e| get_component::<Position>(&loaded, **e) == Some(&Position(3, 4))).unwrap();
+        assert_eq!(get_component::<Link>(&loaded, loaded_a), Some(&Link(Some(loaded_b))));
+        assert_eq!(get_component::<Link>(&loaded, loaded_b), Some(&Link(Some(loaded_a))));
+        assert_eq!(get_resource::<Generation>(&loaded), Some(&Generation(42)));
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        let registry = test_registry();
+        let mut world = create_world_with_registry(registry.clone());
+        let a = add_entity(&mut world);
+        add_component(&mut world, a, Position(1, 2));
+        let bytes = save_world(&world).unwrap();
+
+        assert_eq!(load_world(b"NOPE", registry.clone()).err(), Some(SnapshotError::InvalidMagic));
+        assert!(matches!(load_world(&bytes[..bytes.len() - 3], registry.clone()), Err(SnapshotError::UnexpectedEnd { .. })));
+
+        let mut huge_row_count = bytes.clone();
+        let row_count_offset = 4 + 4 + 4 + 4 + "position".len() + 4 + 4 + 4 + 4;
+        assert_eq!(huge_row_count[row_count_offset..row_count_offset + 8], 1u64.to_le_bytes());
+        huge_row_count[row_count_offset..row_count_offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert_eq!(load_world(&huge_row_count, registry.clone()).err(), Some(SnapshotError::InvalidValue("row count")));
+
+        let mut reader = snapshot_reader(&[1, 2, 3]);
+        reader.read_u8().unwrap();
+        assert_eq!(reader.read_u32(), Err(SnapshotError::UnexpectedEnd { position: 1 }));
+
+        let mut future = bytes.clone();
+        future[4] = 99;
+        assert_eq!(load_world(&future, registry).err(), Some(SnapshotError::UnsupportedVersion(99)));
+        assert!(matches!(load_world(&bytes, Arc::new(component_registry())), Err(SnapshotError::UnknownComponent(_))));
+    }
+
+    #[test]
+    fn rejects_dangling_references() {
+        let registry = test_registry();
+        let mut world = create_world_with_registry(registry.clone());
+        let a = add_entity(&mut world);
+        let never_located = add_entity(&mut world);
+        add_component(&mut world, a, Link(Some(never_located)));
+
+        let bytes = save_world(&world).unwrap();
+        assert_eq!(load_world(&bytes, registry).err(), Some(SnapshotError::UnknownEntity(1)));
+    }
+
+    #[test]
+    fn unregistered_components_cannot_be_saved() {
+        let mut world = create_world();
+        let a = add_entity(&mut world);
+        add_component(&mut world, a, Position(1, 2));
+        assert!(matches!(save_world(&world), Err(SnapshotError::ComponentNotRegistered(_))));
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    any::*,
+    fmt
+};
+
+use super::*;
+
+pub trait SharedComponent: Component + Clone + PartialEq {}
+
+impl<T: Component + Clone + PartialEq> SharedComponent for T {}
+
+type SharedValueEquals = fn(&(dyn Any + Send + Sync), &(dyn Any + Send + Sync)) -> bool;
+
+#[derive(Clone)]
+pub struct SharedComponentValue {
+    pub component_type_id: ComponentTypeId,
+    value: Arc<dyn Any + Send + Sync>,
+    equals: SharedValueEquals
+}
+
+pub fn shared_component_value<S: SharedComponent>(value: S) -> SharedComponentValue {
+    SharedComponentValue {
+        component_type_id: component_type_of::<S>(),
+        value: Arc::new(value),
+        equals: |value, other| value.downcast_ref::<S>() == other.downcast_ref::<S>()
+    }
+}
+
+pub fn downcast_shared_value<S: SharedComponent>(value: &SharedComponentValue) -> Option<&S> {
+    value.value.downcast_ref::<S>()
+}
+
+pub fn shared_value_as_any(value: &SharedComponentValue) -> &(dyn Any + Send + Sync) {
+    value.value.as_ref()
+}
+
+impl PartialEq for SharedComponentValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.component_type_id == other.component_type_id && (self.equals)(self.value.as_ref(), other.value.as_ref())
+    }
+}
+
+impl Eq for SharedComponentValue {}
+
+impl fmt::Debug for SharedComponentValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedComponentValue({})", component_type_name(self.component_type_id))
+    }
+}
+
+pub fn set_shared_component<S: SharedComponent>(world: &mut World, entity: Entity, value: S) {
+    let value = shared_component_value(value);
+    let source_layout = if entity_is_located(&world.entities.location_map, entity) {
+        let location = get_entity_location(&world.entities.location_map, entity);
+        get_archetype_for_entity_location(&world.archetypes, location).layout.clone()
+    } else {
+        create_empty_entity_layout()
+    };
+    if get_layout_shared_value(&source_layout, value.component_type_id) == Some(&value) {
+        return;
+    }
+
+    let target_layout = clone_entity_layout_and_set_shared(&source_layout, value);
+    move_entity_to_layout(world, entity, &target_layout);
+    validate_structural_change(world);
+}
+
+pub fn remove_shared_component<S: SharedComponent>(world: &mut World, entity: Entity) -> Option<S> {
+    let value = get_shared_component::<S>(world, entity)?.clone();
+    let location = get_entity_location(&world.entities.location_map, entity);
+    let source_layout = &get_archetype_for_entity_location(&world.archetypes, location).layout;
+    let target_layout = clone_entity_layout_and_remove_shared(source_layout, component_type_of::<S>());
+    if is_entity_layout_empty(&target_layout) {
+        remove_entity_row(world, entity);
+    } else {
+        move_entity_to_layout(world, entity, &target_layout);
+    }
+    validate_structural_change(world);
+    Some(value)
+}
+
+pub fn get_shared_component<S: SharedComponent>(world: &World, entity: Entity) -> Option<&S> {
+    if !entity_is_located(&world.entities.location_map, entity) {
+        return None;
+    }
+
+    let location = get_entity_location(&world.entities.location_map, entity);
+    let archetype = get_archetype_for_entity_location(&world.archetypes, location);
+    let chunk = get_chunk(&archetype.chunks, location.location_in_archetype.chunk);
+    get_chunk_shared_component(chunk)
+}
+
+pub fn get_chunk_shared_component<S: SharedComponent>(chunk: &ComponentChunk) -> Option<&S> {
+    get_chunk_shared_value(chunk, component_type_of::<S>()).and_then(downcast_shared_value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Tile { x: i32, y: i32 }
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(i32, i32);
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Alive(bool);
+
+    fn tile_of(x: i32, y: i32) -> Tile {
+        Tile { x: x.div_euclid(4), y: y.div_euclid(4) }
+    }
+
+    fn spawn_cell(world: &mut World, x: i32, y: i32) -> Entity {
+        let entity = add_entity(world);
+        add_component(world, entity, Position(x, y));
+        set_shared_component(world, entity, tile_of(x, y));
+        add_component(world, entity, Alive(x == y));
+        entity
+    }
+
+    #[test]
+    fn archetypes_are_partitioned_by_shared_value() {
+        let mut world = create_world();
+        let cells: Vec<Entity> = (0..8)
+            .flat_map(|y| (0..8).map(move |x| (x, y)))
+            .map(|(x, y)| spawn_cell(&mut world, x, y))
+            .collect();
+
+        assert_eq!(world_stats(&world).archetypes.iter().filter(|archetype| archetype.entity_count > 0).count(), 4);
+
+        let mut tiles_seen = Vec::<Tile>::default();
+        for chunk in iterate_query_chunks::<Position, Alive>(&world) {
+            let tile = *get_chunk_shared_component::<Tile>(chunk).unwrap();
+            tiles_seen.push(tile);
+            for (position, _) in iterate_chunk::<Position, Alive>(chunk) {
+                assert_eq!(tile_of(position.0, position.1), tile);
+            }
+        }
+        assert_eq!(tiles_seen.len(), 4);
+
+        let awake = Tile { x: 1, y: 1 };
+        let awake_cells: usize = iterate_query_chunks::<Position, Alive>(&world)
+            .filter(|chunk| get_chunk_shared_component::<Tile>(chunk) == Some(&awake))
+            .map(|chunk| iterate_chunk::<Position, Alive>(chunk).count())
+            .sum();
+        assert_eq!(awake_cells, 16);
+
+        let cell = cells[0];
+        set_shared_component(&mut world, cell, Tile { x: 1, y: 1 });
+        assert_eq!(get_shared_component::<Tile>(&world, cell), Some(&awake));
+        assert_eq!(get_component::<Position>(&world, cell), Some(&Position(0, 0)));
+        assert_eq!(get_component::<Alive>(&world, cell), Some(&Alive(true)));
+        for (index, other) in cells.iter().enumerate().skip(1) {
+            assert_eq!(get_component::<Position>(&world, *other), Some(&Position(index as i32 % 8, index as i32 / 8)));
+        }
+
+        assert_eq!(remove_shared_component::<Tile>(&mut world, cell), Some(awake));
+        assert_eq!(get_shared_component::<Tile>(&world, cell), None);
+        assert_eq!(get_component::<Position>(&world, cell), Some(&Position(0, 0)));
+    }
+
+    #[test]
+    fn shared_components_survive_snapshots() {
+        let mut registry = component_registry();
+        register_component::<Tile>(&mut registry, "Tile");
+        register_shared_component_serialization::<Tile>(&mut registry, |tile, writer| {
+            writer.write_i32(tile.x);
+            writer.write_i32(tile.y);
+        }, |reader| Ok(Tile { x: reader.read_i32()?, y: reader.read_i32()? }));
+        register_component::<Position>(&mut registry, "Position");
+        register_component_serialization::<Position>(&mut registry, |position, writer| {
+            writer.write_i32(position.0);
+            writer.write_i32(position.1);
+        }, |reader| Ok(Position(reader.read_i32()?, reader.read_i32()?)));
+        let registry = Arc::new(registry);
+
+        let mut world = create_world_with_registry(registry.clone());
+        for (x, y) in [(0, 0), (5, 1), (6, 7)] {
+            let entity = add_entity(&mut world);
+            add_component(&mut world, entity, Position(x, y));
+            set_shared_component(&mut world, entity, tile_of(x, y));
+        }
+
+        let loaded = load_world(&save_world(&world).unwrap(), registry).unwrap();
+        let chunks: Vec<&ComponentChunk> = archetype_iter(&loaded.archetypes)
+            .flat_map(|archetype| chunk_iter(&archetype.chunks))
+            .collect();
+        assert_eq!(chunks.len(), 3);
+        for chunk in chunks {
+            let tile = get_chunk_shared_component::<Tile>(chunk).unwrap();
+            for component_index in chunk_component_indicies(chunk) {
+                let position = Position::fetch(chunk, component_index);
+                assert_eq!(tile_of(position.0, position.1), *tile);
+            }
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::iter::{FusedIterator, IntoIterator};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SliceVec<T> {
     data: Vec<T>,
     counts: Vec<usize>,
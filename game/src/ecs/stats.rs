@@ -0,0 +1,144 @@
+use std::fmt;
+
+use super::*;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChunkStats {
+    pub entity_count: usize,
+    pub max_slots: usize,
+    pub fill_ratio: f32,
+    pub bytes_used: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchetypeStats {
+    pub component_names: Vec<&'static str>,
+    pub entity_count: usize,
+    pub chunk_count: usize,
+    pub chunks: Vec<ChunkStats>,
+    pub bytes_used: usize,
+    pub bytes_reserved: usize,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ChunkPoolStats {
+    pub recycled_chunk_count: usize,
+    pub recycled_bytes: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldStats {
+    pub archetypes: Vec<ArchetypeStats>,
+    pub chunk_pool: ChunkPoolStats,
+    pub entity_count: usize,
+    pub chunk_count: usize,
+    pub bytes_used: usize,
+    pub bytes_reserved: usize,
+}
+
+pub fn world_stats(world: &World) -> WorldStats {
+    let archetypes: Vec<ArchetypeStats> = archetype_iter(&world.archetypes)
+        .map(archetype_stats)
+        .collect();
+
+    WorldStats {
+        entity_count: archetypes.iter().map(|archetype| archetype.entity_count).sum(),
+        chunk_count: archetypes.iter().map(|archetype| archetype.chunk_count).sum(),
+        bytes_used: archetypes.iter().map(|archetype| archetype.bytes_used).sum(),
+        bytes_reserved: archetypes.iter().map(|archetype| archetype.bytes_reserved).sum(),
+        chunk_pool: chunk_pool_stats(get_chunk_pool(&world.archetypes)),
+        archetypes,
+    }
+}
+
+fn archetype_stats(archetype: &Archetype) -> ArchetypeStats {
+    let chunks: Vec<ChunkStats> = chunk_iter(&archetype.chunks)
+        .map(chunk_stats)
+        .collect();
+
+    ArchetypeStats {
+        component_names: copy_layout_components(&archetype.layout).map(component_type_name).collect(),
+        entity_count: chunks.iter().map(|chunk| chunk.entity_count).sum(),
+        chunk_count: chunks.len(),
+        bytes_used: chunks.iter().map(|chunk| chunk.bytes_used).sum(),
+        bytes_reserved: chunks.len() * chunk_size(),
+        chunks,
+    }
+}
+
+fn chunk_stats(chunk: &ComponentChunk) -> ChunkStats {
+    let entity_count = chunk_entity_count(chunk);
+    let max_slots = chunk_max_slots(chunk);
+    ChunkStats {
+        entity_count,
+        max_slots,
+        fill_ratio: entity_count as f32 / max_slots as f32,
+        bytes_used: chunk_bytes_used(chunk),
+    }
+}
+
+fn chunk_pool_stats(chunk_pool: &ComponentChunkPool) -> ChunkPoolStats {
+    let recycled_chunk_count = recycled_chunk_count(chunk_pool);
+    ChunkPoolStats {
+        recycled_chunk_count,
+        recycled_bytes: recycled_chunk_count * chunk_size(),
+    }
+}
+
+impl fmt::Display for WorldStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "entities: {}, chunks: {}, used: {}b, reserved: {}b", self.entity_count, self.chunk_count, self.bytes_used, self.bytes_reserved)?;
+        for archetype in &self.archetypes {
+            writeln!(f, "  [{}]", archetype.component_names.join(", "))?;
+            writeln!(f, "    entities: {}, chunks: {}, used: {}b, reserved: {}b", archetype.entity_count, archetype.chunk_count, archetype.bytes_used, archetype.bytes_reserved)?;
+            for (index, chunk) in archetype.chunks.iter().enumerate() {
+                writeln!(f, "    chunk {}: {}/{} ({:.1}%), {}b", index, chunk.entity_count, chunk.max_slots, chunk.fill_ratio * 100.0, chunk.bytes_used)?;
+            }
+        }
+        write!(f, "recycled chunks: {}, {}b", self.chunk_pool.recycled_chunk_count, self.chunk_pool.recycled_bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[allow(dead_code)]
+    struct Position(f32, f32);
+    #[allow(dead_code)]
+    struct Health(u32);
+
+    #[test]
+    fn empty_world() {
+        let stats = world_stats(&create_world());
+        assert_eq!(stats.archetypes.len(), 0);
+        assert_eq!(stats.entity_count, 0);
+        assert_eq!(stats.chunk_pool.recycled_chunk_count, 0);
+    }
+
+    #[test]
+    fn reports_archetypes() {
+        let mut world = create_world();
+        let a = add_entity(&mut world);
+        let b = add_entity(&mut world);
+        add_component(&mut world, a, Position(0.0, 0.0));
+        add_component(&mut world, b, Position(1.0, 0.0));
+        add_component(&mut world, b, Health(10));
+
+        let stats = world_stats(&world);
+        assert_eq!(stats.archetypes.len(), 2);
+        assert_eq!(stats.entity_count, 2);
+
+        let positions = &stats.archetypes[0];
+        assert_eq!(positions.component_names.len(), 1);
+        assert!(positions.component_names[0].ends_with("Position"));
+        assert_eq!(positions.entity_count, 1);
+        assert_eq!(positions.chunk_count, 1);
+        assert_eq!(positions.bytes_used, 8);
+        assert_eq!(positions.chunks[0].max_slots, chunk_size() / (8 + std::mem::size_of::<Entity>()));
+
+        let healthy = &stats.archetypes[1];
+        assert_eq!(healthy.entity_count, 1);
+        assert_eq!(healthy.bytes_used, 12);
+    }
+}
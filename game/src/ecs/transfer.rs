@@ -0,0 +1,329 @@
+use std::{
+    collections::*,
+    fmt
+};
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransferError {
+    UnknownEntity(Entity),
+    DuplicateEntity(Entity),
+    ComponentNotRegistered(&'static str),
+    ComponentMismatch(&'static str),
+    ComponentNotCloneable(&'static str),
+    UntransferredReference(Entity),
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferError::UnknownEntity(entity) => write!(f, "{:?} has no components to transfer", entity),
+            TransferError::DuplicateEntity(entity) => write!(f, "{:?} is listed more than once", entity),
+            TransferError::ComponentNotRegistered(key) => write!(f, "component '{}' is not registered in the target world", key),
+            TransferError::ComponentMismatch(key) => write!(f, "component '{}' is registered with a different type in the target world", key),
+            TransferError::ComponentNotCloneable(key) => write!(f, "component '{}' is not registered as Copy or Clone", key),
+            TransferError::UntransferredReference(entity) => write!(f, "{:?} is referenced by a transferred entity but is not transferred itself", entity),
+        }
+    }
+}
+
+pub fn move_entity_to_world(source: &mut World, target: &mut World, entity: Entity) -> Result<Entity, TransferError> {
+    Ok(move_entities_to_world(source, target, &[entity])?[0])
+}
+
+pub fn copy_entity_to_world(source: &World, target: &mut World, entity: Entity) -> Result<Entity, TransferError> {
+    Ok(copy_entities_to_world(source, target, &[entity])?[0])
+}
+
+pub fn move_entities_to_world(source: &mut World, target: &mut World, entities: &[Entity]) -> Result<Vec<Entity>, TransferError> {
+    validate_transfer(source, target, entities, false)?;
+    if let Some(entity) = find_untransferred_reference(source, entities, entities) {
+        return Err(TransferError::UntransferredReference(entity));
+    }
+    let transferred: Vec<Entity> = entities
+        .iter()
+        .map(|entity| {
+            run_component_hooks(source, *entity, despawn_registered_component);
+            let transferred = transfer_entity_row(source, target, *entity, false);
+            remove_entity_row(source, *entity);
+            transferred
+        })
+        .collect();
+    map_transferred_entities(target, entities, &transferred);
+    run_arrival_hooks(target, &transferred);
+    validate_structural_change(source);
+    validate_structural_change(target);
+    Ok(transferred)
+}
+
+pub fn copy_entities_to_world(source: &World, target: &mut World, entities: &[Entity]) -> Result<Vec<Entity>, TransferError> {
+    copy_entities(source, target, entities, false)
+}
+
+pub(crate) fn copy_detached_hierarchy_to_world(source: &World, target: &mut World, entities: &[Entity]) -> Result<Vec<Entity>, TransferError> {
+    copy_entities(source, target, entities, true)
+}
+
+fn copy_entities(source: &World, target: &mut World, entities: &[Entity], detach_root: bool) -> Result<Vec<Entity>, TransferError> {
+    validate_transfer(source, target, entities, true)?;
+    let transferred: Vec<Entity> = entities
+        .iter()
+        .map(|entity| transfer_entity_row(source, target, *entity, true))
+        .collect();
+    if detach_root {
+        remove_component::<Parent>(target, transferred[0]);
+    }
+    if let Some(entity) = find_untransferred_reference(target, &transferred, entities) {
+        for copied in transferred {
+            despawn_row(target, copied);
+        }
+        return Err(TransferError::UntransferredReference(entity));
+    }
+    map_transferred_entities(target, entities, &transferred);
+    run_arrival_hooks(target, &transferred);
+    validate_structural_change(target);
+    Ok(transferred)
+}
+
+fn run_arrival_hooks(target: &mut World, transferred: &[Entity]) {
+    for entity in transferred {
+        if entity_is_located(&target.entities.location_map, *entity) {
+            run_component_hooks(target, *entity, arrive_registered_component);
+        }
+    }
+}
+
+pub fn clone_entity(world: &mut World, entity: Entity) -> Result<Entity, TransferError> {
+    Ok(clone_entities(world, &[entity])?[0])
+}
+
+pub fn clone_entities(world: &mut World, entities: &[Entity]) -> Result<Vec<Entity>, TransferError> {
+    validate_transfer(world, world, entities, true)?;
+    let cloned: Vec<Entity> = entities
+        .iter()
+        .map(|entity| clone_entity_row(world, *entity))
+        .collect();
+    map_transferred_entities(world, entities, &cloned);
+    link_cloned_hierarchy(world, &cloned);
+    validate_structural_change(world);
+    Ok(cloned)
+}
+
+fn validate_transfer(source: &World, target: &World, entities: &[Entity], clone: bool) -> Result<(), TransferError> {
+    let mut seen = HashSet::<Entity>::default();
+    for entity in entities {
+        if !seen.insert(*entity) {
+            return Err(TransferError::DuplicateEntity(*entity));
+        }
+        if !entity_is_located(&source.entities.location_map, *entity) {
+            return Err(TransferError::UnknownEntity(*entity));
+        }
+
+        let location = get_entity_location(&source.entities.location_map, *entity);
+        let archetype = get_archetype_for_entity_location(&source.archetypes, location);
+        for component_type_id in &archetype.layout.components {
+            validate_component_transfer(source, target, *component_type_id, clone)?;
+        }
+    }
+    Ok(())
+}
+
+fn validate_component_transfer(source: &World, target: &World, component_type_id: ComponentTypeId, clone: bool) -> Result<(), TransferError> {
+    let registration = match get_component_registration(&source.registry, component_type_id) {
+        Some(registration) => registration,
+        None if clone => return Err(TransferError::ComponentNotRegistered(component_type_name(component_type_id))),
+        None => return Ok(())
+    };
+    let target_registration = get_component_registration_by_key(&target.registry, registration.key)
+        .ok_or(TransferError::ComponentNotRegistered(registration.key))?;
+    if target_registration.component_type_id != component_type_id {
+        return Err(TransferError::ComponentMismatch(registration.key));
+    }
+    if clone && !is_registered_component_cloneable(registration) {
+        return Err(TransferError::ComponentNotCloneable(registration.key));
+    }
+    Ok(())
+}
+
+fn transfer_entity_row(source: &World, target: &mut World, entity: Entity, clone: bool) -> Entity {
+    let location = get_entity_location(&source.entities.location_map, entity);
+    let source_archetype = get_archetype_for_entity_location(&source.archetypes, location);
+    let layout = source_archetype.layout.clone();
+    copy_component_storages_for_layout(&source.component_storages, &mut target.component_storages, &layout);
+    create_archetype_if_non_existant(&mut target.archetypes, &layout);
+
+    let target_entity = add_entity(target);
+    let target_location = move_to_next_archetype_entity_location(&mut target.archetypes, &layout);
+    let target_archetype = get_archetype_for_layout(&target.archetypes, &layout).unwrap();
+    let source_chunk = get_chunk(&source_archetype.chunks, location.location_in_archetype.chunk);
+    let target_chunk = get_chunk(&target_archetype.chunks, target_location.chunk);
+
+    if clone {
+        clone_row_between_chunks(source_chunk, location.location_in_archetype.component, target_chunk, target_location.component, |component_type_id, source_bytes, target_bytes| {
+            let registration = get_component_registration(&source.registry, component_type_id).unwrap();
+            clone_registered_component_stream(registration, source_bytes, target_bytes);
+        });
+    } else {
+        copy_row_between_chunks(source_chunk, location.location_in_archetype.component, target_chunk, target_location.component);
+    }
+    store_entity_at_location(&target_archetype.chunks, target_location, target_entity);
+
+    let target_archetype_index = target_archetype.index;
+    add_entity_to_location(&mut target.entities.location_map, target_entity, target_archetype_index, target_location);
+    target_entity
+}
+
+fn clone_entity_row(world: &mut World, entity: Entity) -> Entity {
+    let location = get_entity_location(&world.entities.location_map, entity);
+    let layout = get_archetype_for_entity_location(&world.archetypes, location).layout.clone();
+    let registry = world.registry.clone();
+
+    let cloned_entity = add_entity(world);
+    let cloned_location = move_to_next_archetype_entity_location(&mut world.archetypes, &layout);
+    let archetype = get_archetype_for_layout(&world.archetypes, &layout).unwrap();
+    let source_chunk = get_chunk(&archetype.chunks, location.location_in_archetype.chunk);
+    let target_chunk = get_chunk(&archetype.chunks, cloned_location.chunk);
+    clone_row_between_chunks(source_chunk, location.location_in_archetype.component, target_chunk, cloned_location.component, |component_type_id, source_bytes, target_bytes| {
+        let registration = get_component_registration(&registry, component_type_id).unwrap();
+        clone_registered_component_stream(registration, source_bytes, target_bytes);
+    });
+    store_entity_at_location(&archetype.chunks, cloned_location, cloned_entity);
+
+    let archetype_index = archetype.index;
+    add_entity_to_location(&mut world.entities.location_map, cloned_entity, archetype_index, cloned_location);
+    cloned_entity
+}
+
+fn find_untransferred_reference(world: &mut World, rows: &[Entity], transferred: &[Entity]) -> Option<Entity> {
+    let transferred: HashSet<Entity> = transferred.iter().copied().collect();
+    let untransferred = std::cell::Cell::new(None);
+    let find = |entity: Entity| {
+        if !transferred.contains(&entity) && untransferred.get().is_none() {
+            untransferred.set(Some(entity));
+        }
+        entity
+    };
+    let registry = world.registry.clone();
+
+    for row in rows {
+        let location = get_entity_location(&world.entities.location_map, *row);
+        let components = get_archetype_for_entity_location(&world.archetypes, location).layout.components.clone();
+        for component_type_id in components {
+            if let Some(registration) = get_component_registration(&registry, component_type_id) {
+                map_registered_component_entities(registration, world, *row, &find);
+            }
+        }
+    }
+    untransferred.get()
+}
+
+fn map_transferred_entities(target: &mut World, entities: &[Entity], transferred: &[Entity]) {
+    let entity_map: HashMap<Entity, Entity> = entities.iter().copied().zip(transferred.iter().copied()).collect();
+    let map = |entity: Entity| entity_map.get(&entity).copied().unwrap_or(entity);
+    let registry = target.registry.clone();
+
+    for entity in transferred {
+        let location = get_entity_location(&target.entities.location_map, *entity);
+        let components = get_archetype_for_entity_location(&target.archetypes, location).layout.components.clone();
+        for component_type_id in components {
+            if let Some(registration) = get_component_registration(&registry, component_type_id) {
+                map_registered_component_entities(registration, target, *entity, &map);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(u32, u32);
+    #[derive(Debug, Clone, PartialEq)]
+    struct Name(String);
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Link(Option<Entity>);
+
+    fn create_test_registry() -> Arc<ComponentRegistry> {
+        let mut registry = component_registry();
+        register_component::<Position>(&mut registry, "Position");
+        register_component_copy::<Position>(&mut registry);
+        register_component::<Name>(&mut registry, "Name");
+        register_component_clone::<Name>(&mut registry);
+        register_component::<Link>(&mut registry, "Link");
+        register_component_copy::<Link>(&mut registry);
+        register_component_entity_references::<Link>(&mut registry, |link, map| link.0 = link.0.map(map));
+        Arc::new(registry)
+    }
+
+    fn spawn(world: &mut World, index: u32) -> Entity {
+        let entity = add_entity(world);
+        add_component(world, entity, Position(index, index));
+        add_component(world, entity, Name(format!("cell {}", index)));
+        entity
+    }
+
+    #[test]
+    fn moving_entities_remaps_links_and_keeps_source_consistent() {
+        let registry = create_test_registry();
+        let mut source = create_world_with_registry(registry.clone());
+        let mut scratch = create_world_with_registry(registry);
+        let entities: Vec<Entity> = (0..4).map(|index| spawn(&mut source, index)).collect();
+        add_component(&mut source, entities[0], Link(Some(entities[1])));
+        add_component(&mut source, entities[1], Link(Some(entities[0])));
+
+        let moved = move_entities_to_world(&mut source, &mut scratch, &entities[0..2]).unwrap();
+
+        assert_eq!(get_component::<Link>(&scratch, moved[0]), Some(&Link(Some(moved[1]))));
+        assert_eq!(get_component::<Link>(&scratch, moved[1]), Some(&Link(Some(moved[0]))));
+        assert_eq!(get_component::<Name>(&scratch, moved[1]), Some(&Name("cell 1".to_string())));
+        assert_eq!(get_component::<Position>(&source, entities[0]), None);
+        assert_eq!(world_stats(&source).entity_count, 2);
+        for (index, entity) in entities.iter().enumerate().skip(2) {
+            assert_eq!(get_component::<Position>(&source, *entity), Some(&Position(index as u32, index as u32)));
+        }
+    }
+
+    #[test]
+    fn copying_entity_clones_components() {
+        let registry = create_test_registry();
+        let mut source = create_world_with_registry(registry.clone());
+        let mut clipboard = create_world_with_registry(registry);
+        let entity = spawn(&mut source, 5);
+
+        let copied = copy_entity_to_world(&source, &mut clipboard, entity).unwrap();
+        get_component_mut::<Name>(&mut source, entity).unwrap().0.push_str(" changed");
+
+        assert_eq!(get_component::<Name>(&clipboard, copied), Some(&Name("cell 5".to_string())));
+        assert_eq!(get_component::<Position>(&clipboard, copied), Some(&Position(5, 5)));
+        assert_eq!(copy_entities_to_world(&source, &mut clipboard, &[entity, entity]), Err(TransferError::DuplicateEntity(entity)));
+    }
+
+    #[test]
+    fn target_registry_must_know_components() {
+        let mut source = create_world_with_registry(create_test_registry());
+        let mut target = create_world();
+        let entity = spawn(&mut source, 0);
+        assert_eq!(move_entity_to_world(&mut source, &mut target, entity), Err(TransferError::ComponentNotRegistered("Position")));
+        assert_eq!(get_component::<Position>(&source, entity), Some(&Position(0, 0)));
+    }
+
+    #[test]
+    fn references_to_entities_left_behind_are_rejected() {
+        let registry = create_test_registry();
+        let mut source = create_world_with_registry(registry.clone());
+        let mut target = create_world_with_registry(registry);
+        let entities: Vec<Entity> = (0..2).map(|index| spawn(&mut source, index)).collect();
+        add_component(&mut source, entities[0], Link(Some(entities[1])));
+
+        let error = Err(TransferError::UntransferredReference(entities[1]));
+        assert_eq!(move_entity_to_world(&mut source, &mut target, entities[0]), error);
+        assert_eq!(copy_entity_to_world(&source, &mut target, entities[0]), error);
+        assert_eq!(world_stats(&target).entity_count, 0);
+        assert_eq!(get_component::<Link>(&source, entities[0]), Some(&Link(Some(entities[1]))));
+
+        let cloned = clone_entity(&mut source, entities[0]).unwrap();
+        assert_eq!(get_component::<Link>(&source, cloned), Some(&Link(Some(entities[1]))));
+    }
+}
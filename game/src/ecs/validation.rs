@@ -0,0 +1,214 @@
+use std::fmt;
+
+use gol_engine::gol_assert;
+
+use super::*;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorldInvariantViolation {
+    MissingArchetype { entity: Entity, archetype: usize },
+    MissingChunk { entity: Entity, archetype: usize, chunk: usize },
+    DeadSlot { entity: Entity, archetype: usize, chunk: usize, row: usize, entity_count: usize },
+    SlotMismatch { entity: Entity, archetype: usize, chunk: usize, row: usize, stored: Entity },
+    SharedSlot { first: Entity, second: Entity, archetype: usize, chunk: usize, row: usize },
+    UnlocatedRow { stored: Entity, archetype: usize, chunk: usize, row: usize },
+    ChunkOverflow { archetype: usize, chunk: usize, current_component_index: usize, max_slots: usize },
+    ChunkIndexMismatch { archetype: usize, position: usize, chunk_index: usize },
+    PartiallyFilledChunk { archetype: usize, chunk: usize, entity_count: usize, max_slots: usize },
+    SearchIndexLength { search_index: usize, archetypes: usize },
+    SearchIndexMismatch { archetype: usize, search_index: Vec<&'static str>, layout: Vec<&'static str> },
+}
+
+impl fmt::Display for WorldInvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorldInvariantViolation::MissingArchetype { entity, archetype } =>
+                write!(f, "{:?} is located in archetype {} which does not exist", entity, archetype),
+            WorldInvariantViolation::MissingChunk { entity, archetype, chunk } =>
+                write!(f, "{:?} is located in chunk {} of archetype {} which does not exist", entity, chunk, archetype),
+            WorldInvariantViolation::DeadSlot { entity, archetype, chunk, row, entity_count } =>
+                write!(f, "{:?} is located at row {} of archetype {} chunk {} which only has {} live rows", entity, row, archetype, chunk, entity_count),
+            WorldInvariantViolation::SlotMismatch { entity, archetype, chunk, row, stored } =>
+                write!(f, "{:?} is located at archetype {} chunk {} row {} but that row belongs to {:?}", entity, archetype, chunk, row, stored),
+            WorldInvariantViolation::SharedSlot { first, second, archetype, chunk, row } =>
+                write!(f, "{:?} and {:?} are both located at archetype {} chunk {} row {}", first, second, archetype, chunk, row),
+            WorldInvariantViolation::UnlocatedRow { stored, archetype, chunk, row } =>
+                write!(f, "archetype {} chunk {} row {} holds {:?} but no entity is located there", archetype, chunk, row, stored),
+            WorldInvariantViolation::ChunkOverflow { archetype, chunk, current_component_index, max_slots } =>
+                write!(f, "archetype {} chunk {} has current component index {} but only {} slots", archetype, chunk, current_component_index, max_slots),
+            WorldInvariantViolation::ChunkIndexMismatch { archetype, position, chunk_index } =>
+                write!(f, "archetype {} chunk at position {} thinks it is chunk {}", archetype, position, chunk_index),
+            WorldInvariantViolation::PartiallyFilledChunk { archetype, chunk, entity_count, max_slots } =>
+                write!(f, "archetype {} chunk {} is not the head chunk but only holds {} of {} rows", archetype, chunk, entity_count, max_slots),
+            WorldInvariantViolation::SearchIndexLength { search_index, archetypes } =>
+                write!(f, "search index has {} layouts but there are {} archetypes", search_index, archetypes),
+            WorldInvariantViolation::SearchIndexMismatch { archetype, search_index, layout } =>
+                write!(f, "search index for archetype {} is {:?} but its layout is {:?}", archetype, search_index, layout),
+        }
+    }
+}
+
+pub fn validate_world(world: &World) {
+    let violations = find_world_invariant_violations(world);
+    if violations.is_empty() {
+        return;
+    }
+    gol_assert!(
+        false,
+        "world invariants violated:\n{}",
+        violations.iter().map(|violation| violation.to_string()).collect::<Vec<_>>().join("\n")
+    );
+}
+
+pub fn find_world_invariant_violations(world: &World) -> Vec<WorldInvariantViolation> {
+    let mut violations = Vec::<WorldInvariantViolation>::default();
+    find_chunk_violations(world, &mut violations);
+    find_search_index_violations(world, &mut violations);
+    find_location_violations(world, &mut violations);
+    violations
+}
+
+fn find_chunk_violations(world: &World, violations: &mut Vec<WorldInvariantViolation>) {
+    for archetype in archetype_iter(&world.archetypes) {
+        let archetype_index = usize::from(archetype.index);
+        let chunk_count = chunk_iter(&archetype.chunks).len();
+        for (position, chunk) in chunk_iter(&archetype.chunks).enumerate() {
+            let chunk_index = usize::from(chunk.chunk_index);
+            let current_component_index = usize::from(chunk.current_component_index);
+            let max_slots = chunk_max_slots(chunk);
+            if chunk_index != position {
+                violations.push(WorldInvariantViolation::ChunkIndexMismatch { archetype: archetype_index, position, chunk_index });
+            }
+            if current_component_index >= max_slots {
+                violations.push(WorldInvariantViolation::ChunkOverflow { archetype: archetype_index, chunk: position, current_component_index, max_slots });
+            } else if position + 1 < chunk_count && !is_chunk_full(chunk) {
+                violations.push(WorldInvariantViolation::PartiallyFilledChunk { archetype: archetype_index, chunk: position, entity_count: chunk_entity_count(chunk), max_slots });
+            }
+        }
+    }
+}
+
+fn find_search_index_violations(world: &World, violations: &mut Vec<WorldInvariantViolation>) {
+    let search_index_count = search_index_iter(&world.archetypes.search_index).count();
+    let archetype_count = archetype_count(&world.archetypes);
+    if search_index_count != archetype_count {
+        violations.push(WorldInvariantViolation::SearchIndexLength { search_index: search_index_count, archetypes: archetype_count });
+    }
+
+    for (archetype, search_index) in archetype_iter(&world.archetypes).zip(search_index_iter(&world.archetypes.search_index)) {
+        let layout: Vec<ComponentTypeId> = copy_layout_components(&archetype.layout)
+            .chain(layout_shared_component_types(&archetype.layout))
+            .collect();
+        if layout != search_index {
+            violations.push(WorldInvariantViolation::SearchIndexMismatch {
+                archetype: usize::from(archetype.index),
+                search_index: search_index.iter().map(|component_type_id| component_type_name(*component_type_id)).collect(),
+                layout: layout.iter().map(|component_type_id| component_type_name(*component_type_id)).collect(),
+            });
+        }
+    }
+}
+
+fn find_location_violations(world: &World, violations: &mut Vec<WorldInvariantViolation>) {
+    let chunk_offsets: Vec<Vec<usize>> = archetype_iter(&world.archetypes)
+        .scan(0, |offset, archetype| {
+            Some(chunk_iter(&archetype.chunks)
+                .map(|chunk| {
+                    let chunk_offset = *offset;
+                    *offset += chunk_max_slots(chunk);
+                    chunk_offset
+                })
+                .collect())
+        })
+        .collect();
+    let slot_count = archetype_iter(&world.archetypes)
+        .flat_map(|archetype| chunk_iter(&archetype.chunks))
+        .map(chunk_max_slots)
+        .sum();
+    let mut occupied = vec!(None::<Entity>; slot_count);
+
+    for (entity, location) in entity_location_iter(&world.entities.location_map) {
+        let archetype_index = usize::from(location.archetype_index);
+        let chunk_index = usize::from(location.location_in_archetype.chunk);
+        let row = usize::from(location.location_in_archetype.component);
+        if archetype_index >= archetype_count(&world.archetypes) {
+            violations.push(WorldInvariantViolation::MissingArchetype { entity, archetype: archetype_index });
+            continue;
+        }
+
+        let archetype = get_archetype(&world.archetypes, location.archetype_index);
+        let chunk = match chunk_iter(&archetype.chunks).nth(chunk_index) {
+            Some(chunk) => chunk,
+            None => {
+                violations.push(WorldInvariantViolation::MissingChunk { entity, archetype: archetype_index, chunk: chunk_index });
+                continue;
+            }
+        };
+        if row >= chunk_entity_count(chunk) {
+            violations.push(WorldInvariantViolation::DeadSlot { entity, archetype: archetype_index, chunk: chunk_index, row, entity_count: chunk_entity_count(chunk) });
+            continue;
+        }
+
+        let stored = get_entity_in_chunk(chunk, location.location_in_archetype.component);
+        if stored != entity {
+            violations.push(WorldInvariantViolation::SlotMismatch { entity, archetype: archetype_index, chunk: chunk_index, row, stored });
+        }
+        if let Some(first) = occupied[chunk_offsets[archetype_index][chunk_index] + row].replace(entity) {
+            violations.push(WorldInvariantViolation::SharedSlot { first, second: entity, archetype: archetype_index, chunk: chunk_index, row });
+        }
+    }
+
+    for (archetype_index, archetype) in archetype_iter(&world.archetypes).enumerate() {
+        for (chunk_index, chunk) in chunk_iter(&archetype.chunks).enumerate() {
+            for component_index in chunk_component_indicies(chunk) {
+                let row = usize::from(component_index);
+                if occupied[chunk_offsets[archetype_index][chunk_index] + row].is_none() {
+                    violations.push(WorldInvariantViolation::UnlocatedRow { stored: get_entity_in_chunk(chunk, component_index), archetype: archetype_index, chunk: chunk_index, row });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Position(u32);
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Alive(bool);
+
+    #[test]
+    fn valid_world_has_no_violations() {
+        let mut world = create_world();
+        for index in 0..600 {
+            let entity = add_entity(&mut world);
+            add_component(&mut world, entity, Position(index));
+            if index % 2 == 0 {
+                add_component(&mut world, entity, Alive(true));
+            }
+            if index % 5 == 0 {
+                despawn(&mut world, entity);
+            }
+        }
+        assert_eq!(find_world_invariant_violations(&world), vec!());
+    }
+
+    #[test]
+    fn detects_corrupted_locations() {
+        let mut world = create_world();
+        let first = add_entity(&mut world);
+        add_component(&mut world, first, Position(0));
+        let second = add_entity(&mut world);
+        add_component(&mut world, second, Position(1));
+
+        let first_location = get_entity_location(&world.entities.location_map, first);
+        change_entity_location(&mut world.entities.location_map, second, first_location.archetype_index, first_location.location_in_archetype);
+
+        let violations = find_world_invariant_violations(&world);
+        assert!(violations.contains(&WorldInvariantViolation::SlotMismatch { entity: second, archetype: 0, chunk: 0, row: 0, stored: first }));
+        assert!(violations.contains(&WorldInvariantViolation::SharedSlot { first, second, archetype: 0, chunk: 0, row: 0 }));
+        assert!(violations.contains(&WorldInvariantViolation::UnlocatedRow { stored: second, archetype: 0, chunk: 0, row: 1 }));
+    }
+}
@@ -0,0 +1,482 @@
+use std::{
+    collections::*,
+    fmt
+};
+
+use crate::{direction_offset, neighbourhood_directions, rule_births, next_cell_state, GridCoordinate, Neighbourhood, Rule};
+
+pub const DEFAULT_HASHLIFE_GC_THRESHOLD: usize = 1 << 20;
+
+const DEAD: NodeId = NodeId(0);
+const ALIVE: NodeId = NodeId(1);
+const INITIAL_LEVEL: u8 = 3;
+const MAX_LEVEL: u8 = 62;
+const MAX_STEP_LOG2: u8 = MAX_LEVEL - 3;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+struct NodeId(u32);
+
+#[derive(Debug, Copy, Clone)]
+struct Node {
+    level: u8,
+    children: [NodeId; 4],
+    population: u64,
+}
+
+pub struct HashLife {
+    nodes: Vec<Node>,
+    index: HashMap<[NodeId; 4], NodeId>,
+    empty: Vec<NodeId>,
+    results: HashMap<(NodeId, u8), NodeId>,
+    root: NodeId,
+    origin: GridCoordinate,
+    rule: Rule,
+    neighbourhood: Neighbourhood,
+    generation: u64,
+    gc_threshold: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HashLifeError {
+    BirthOnZero(Rule),
+    StepTooLarge { step_log2: u8 },
+    UniverseTooLarge,
+}
+
+impl fmt::Display for HashLifeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashLifeError::BirthOnZero(rule) => write!(f, "HashLife cannot run {} because it has B0", rule),
+            HashLifeError::StepTooLarge { step_log2 } => write!(f, "HashLife cannot step 2^{} generations at once, the limit is 2^{}", step_log2, MAX_STEP_LOG2),
+            HashLifeError::UniverseTooLarge => write!(f, "HashLife universe would exceed level {}", MAX_LEVEL),
+        }
+    }
+}
+
+pub fn hashlife(rule: Rule, neighbourhood: Neighbourhood) -> Result<HashLife, HashLifeError> {
+    if rule_births(&rule, 0) {
+        return Err(HashLifeError::BirthOnZero(rule));
+    }
+
+    let mut life = HashLife {
+        nodes: vec!(
+            Node { level: 0, children: [DEAD; 4], population: 0 },
+            Node { level: 0, children: [DEAD; 4], population: 1 },
+        ),
+        index: HashMap::default(),
+        empty: vec!(DEAD),
+        results: HashMap::default(),
+        root: DEAD,
+        origin: (0, 0),
+        rule,
+        neighbourhood,
+        generation: 0,
+        gc_threshold: DEFAULT_HASHLIFE_GC_THRESHOLD,
+    };
+    life.root = empty_node(&mut life, INITIAL_LEVEL);
+    life.origin = (-(1 << (INITIAL_LEVEL - 1)), -(1 << (INITIAL_LEVEL - 1)));
+    Ok(life)
+}
+
+pub fn hashlife_generation(life: &HashLife) -> u64 {
+    life.generation
+}
+
+pub fn hashlife_population(life: &HashLife) -> u64 {
+    node(life, life.root).population
+}
+
+pub fn hashlife_node_count(life: &HashLife) -> usize {
+    life.nodes.len()
+}
+
+pub fn set_hashlife_gc_threshold(life: &mut HashLife, threshold: usize) {
+    life.gc_threshold = threshold;
+}
+
+fn node(life: &HashLife, id: NodeId) -> Node {
+    life.nodes[id.0 as usize]
+}
+
+fn node_level(life: &HashLife, id: NodeId) -> u8 {
+    node(life, id).level
+}
+
+fn child(life: &HashLife, id: NodeId, quadrant: usize) -> NodeId {
+    node(life, id).children[quadrant]
+}
+
+fn join(life: &mut HashLife, children: [NodeId; 4]) -> NodeId {
+    if let Some(id) = life.index.get(&children) {
+        return *id;
+    }
+    let level = node_level(life, children[0]) + 1;
+    let population = children.iter().fold(0u64, |population, child| population.saturating_add(node(life, *child).population));
+    let id = NodeId(life.nodes.len() as u32);
+    life.nodes.push(Node { level, children, population });
+    life.index.insert(children, id);
+    id
+}
+
+fn empty_node(life: &mut HashLife, level: u8) -> NodeId {
+    while life.empty.len() <= level as usize {
+        let below = *life.empty.last().unwrap();
+        let empty = join(life, [below; 4]);
+        life.empty.push(empty);
+    }
+    life.empty[level as usize]
+}
+
+fn root_size(life: &HashLife) -> i64 {
+    1 << node_level(life, life.root)
+}
+
+fn expand(life: &mut HashLife) {
+    let level = node_level(life, life.root);
+    let empty = empty_node(life, level - 1);
+    let [nw, ne, sw, se] = node(life, life.root).children;
+    let children = [
+        join(life, [empty, empty, empty, nw]),
+        join(life, [empty, empty, ne, empty]),
+        join(life, [empty, sw, empty, empty]),
+        join(life, [se, empty, empty, empty]),
+    ];
+    life.root = join(life, children);
+    let half = 1i64 << (level - 1);
+    life.origin = (life.origin.0 - half, life.origin.1 - half);
+}
+
+fn try_expand(life: &mut HashLife) -> Result<(), HashLifeError> {
+    if node_level(life, life.root) >= MAX_LEVEL {
+        return Err(HashLifeError::UniverseTooLarge);
+    }
+    expand(life);
+    Ok(())
+}
+
+fn contains(life: &HashLife, (x, y): GridCoordinate) -> bool {
+    let size = root_size(life);
+    (life.origin.0..life.origin.0 + size).contains(&x) && (life.origin.1..life.origin.1 + size).contains(&y)
+}
+
+fn is_padded(life: &HashLife) -> bool {
+    let root = node(life, life.root);
+    root.children.iter().enumerate().all(|(quadrant, quarter)| {
+        let inner = child(life, *quarter, 3 - quadrant);
+        node(life, inner).population == node(life, *quarter).population
+    })
+}
+
+pub fn set_hashlife_cell(life: &mut HashLife, coordinate: GridCoordinate, alive: bool) -> Result<(), HashLifeError> {
+    while !contains(life, coordinate) {
+        try_expand(life)?;
+    }
+    let (x, y) = (coordinate.0 - life.origin.0, coordinate.1 - life.origin.1);
+    life.root = set_node_cell(life, life.root, x, y, alive);
+    Ok(())
+}
+
+fn set_node_cell(life: &mut HashLife, id: NodeId, x: i64, y: i64, alive: bool) -> NodeId {
+    let level = node_level(life, id);
+    if level == 0 {
+        return if alive { ALIVE } else { DEAD };
+    }
+    let half = 1i64 << (level - 1);
+    let quadrant = (y >= half) as usize * 2 + (x >= half) as usize;
+    let mut children = node(life, id).children;
+    children[quadrant] = set_node_cell(life, children[quadrant], x % half, y % half, alive);
+    join(life, children)
+}
+
+pub fn get_hashlife_cell(life: &HashLife, coordinate: GridCoordinate) -> bool {
+    hashlife_range_population(life, coordinate, (coordinate.0 + 1, coordinate.1 + 1)) > 0
+}
+
+pub fn hashlife_range_population(life: &HashLife, min: GridCoordinate, max: GridCoordinate) -> u64 {
+    range_population(life, life.root, life.origin, min, max)
+}
+
+fn range_population(life: &HashLife, id: NodeId, (x, y): GridCoordinate, min: GridCoordinate, max: GridCoordinate) -> u64 {
+    let current = node(life, id);
+    let size = 1i64 << current.level;
+    if current.population == 0 || x >= max.0 || y >= max.1 || x + size <= min.0 || y + size <= min.1 {
+        return 0;
+    }
+    if min.0 <= x && min.1 <= y && x + size <= max.0 && y + size <= max.1 {
+        return current.population;
+    }
+    let half = size / 2;
+    current.children
+        .iter()
+        .enumerate()
+        .map(|(quadrant, child)| range_population(life, *child, (x + half * (quadrant % 2) as i64, y + half * (quadrant / 2) as i64), min, max))
+        .sum()
+}
+
+pub fn hashlife_live_cells(life: &HashLife) -> Vec<GridCoordinate> {
+    let mut live = Vec::<GridCoordinate>::default();
+    collect_live_cells(life, life.root, life.origin, &mut live);
+    live.sort_by_key(|(x, y)| (*y, *x));
+    live
+}
+
+fn collect_live_cells(life: &HashLife, id: NodeId, (x, y): GridCoordinate, live: &mut Vec<GridCoordinate>) {
+    let current = node(life, id);
+    if current.population == 0 {
+        return;
+    }
+    if current.level == 0 {
+        live.push((x, y));
+        return;
+    }
+    let half = 1i64 << (current.level - 1);
+    for (quadrant, child) in current.children.iter().enumerate() {
+        collect_live_cells(life, *child, (x + half * (quadrant % 2) as i64, y + half * (quadrant / 2) as i64), live);
+    }
+}
+
+pub fn step_hashlife(life: &mut HashLife, generations: u64) -> Result<(), HashLifeError> {
+    let highest_step_log2 = (u64::BITS - generations.leading_zeros()).saturating_sub(1) as u8;
+    if highest_step_log2 > MAX_STEP_LOG2 {
+        return Err(HashLifeError::StepTooLarge { step_log2: highest_step_log2 });
+    }
+    for step_log2 in 0..=highest_step_log2 {
+        if generations >> step_log2 & 1 != 0 {
+            step_hashlife_pow2(life, step_log2)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn step_hashlife_pow2(life: &mut HashLife, step_log2: u8) -> Result<(), HashLifeError> {
+    if step_log2 > MAX_STEP_LOG2 {
+        return Err(HashLifeError::StepTooLarge { step_log2 });
+    }
+    while node_level(life, life.root) < step_log2 + 2 || !is_padded(life) {
+        try_expand(life)?;
+    }
+    try_expand(life)?;
+
+    let level = node_level(life, life.root);
+    life.root = result(life, life.root, step_log2);
+    let quarter = 1i64 << (level - 2);
+    life.origin = (life.origin.0 + quarter, life.origin.1 + quarter);
+    life.generation += 1 << step_log2;
+
+    if life.nodes.len() > life.gc_threshold {
+        collect_hashlife_garbage(life);
+        if life.nodes.len() > life.gc_threshold / 2 {
+            life.gc_threshold *= 2;
+        }
+    }
+    Ok(())
+}
+
+fn result(life: &mut HashLife, id: NodeId, step_log2: u8) -> NodeId {
+    let level = node_level(life, id);
+    let step_log2 = step_log2.min(level - 2);
+    if let Some(result) = life.results.get(&(id, step_log2)) {
+        return *result;
+    }
+
+    let result = if node(life, id).population == 0 {
+        empty_node(life, level - 1)
+    } else if level == 2 {
+        base_result(life, id)
+    } else {
+        let [nw, ne, sw, se] = node(life, id).children;
+        let [_, nw_ne, nw_sw, nw_se] = node(life, nw).children;
+        let [ne_nw, _, ne_sw, ne_se] = node(life, ne).children;
+        let [sw_nw, sw_ne, _, sw_se] = node(life, sw).children;
+        let [se_nw, se_ne, se_sw, _] = node(life, se).children;
+        let sub_nodes = [
+            nw,
+            join(life, [nw_ne, ne_nw, nw_se, ne_sw]),
+            ne,
+            join(life, [nw_sw, nw_se, sw_nw, sw_ne]),
+            join(life, [nw_se, ne_sw, sw_ne, se_nw]),
+            join(life, [ne_sw, ne_se, se_nw, se_ne]),
+            sw,
+            join(life, [sw_ne, se_nw, sw_se, se_sw]),
+            se,
+        ];
+        let full_speed = step_log2 == level - 2;
+        let mut advanced = [DEAD; 9];
+        for (index, sub_node) in sub_nodes.iter().enumerate() {
+            advanced[index] = if full_speed { result(life, *sub_node, step_log2) } else { centre(life, *sub_node) };
+        }
+        let quarters = [
+            join(life, [advanced[0], advanced[1], advanced[3], advanced[4]]),
+            join(life, [advanced[1], advanced[2], advanced[4], advanced[5]]),
+            join(life, [advanced[3], advanced[4], advanced[6], advanced[7]]),
+            join(life, [advanced[4], advanced[5], advanced[7], advanced[8]]),
+        ];
+        let mut children = [DEAD; 4];
+        for (index, quarter) in quarters.iter().enumerate() {
+            children[index] = result(life, *quarter, step_log2);
+        }
+        join(life, children)
+    };
+
+    life.results.insert((id, step_log2), result);
+    result
+}
+
+fn centre(life: &mut HashLife, id: NodeId) -> NodeId {
+    let [nw, ne, sw, se] = node(life, id).children;
+    let children = [child(life, nw, 3), child(life, ne, 2), child(life, sw, 1), child(life, se, 0)];
+    join(life, children)
+}
+
+fn base_result(life: &mut HashLife, id: NodeId) -> NodeId {
+    let mut cells = [[false; 4]; 4];
+    for (y, row) in cells.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            let quarter = child(life, id, (y / 2) * 2 + x / 2);
+            *cell = child(life, quarter, (y % 2) * 2 + x % 2) == ALIVE;
+        }
+    }
+
+    let directions = neighbourhood_directions(life.neighbourhood);
+    let mut children = [DEAD; 4];
+    for (index, child) in children.iter_mut().enumerate() {
+        let (x, y) = (1 + index % 2, 1 + index / 2);
+        let live_neighbours = directions
+            .iter()
+            .map(|direction| direction_offset(*direction))
+            .filter(|(dx, dy)| cells[(y as i64 + dy) as usize][(x as i64 + dx) as usize])
+            .count();
+        *child = if next_cell_state(&life.rule, cells[y][x], live_neighbours) { ALIVE } else { DEAD };
+    }
+    join(life, children)
+}
+
+pub fn collect_hashlife_garbage(life: &mut HashLife) {
+    let leaves = vec!(node(life, DEAD), node(life, ALIVE));
+    let old_nodes = std::mem::replace(&mut life.nodes, leaves);
+    life.index.clear();
+    life.empty = vec!(DEAD);
+
+    let mut remap = HashMap::<NodeId, NodeId>::from([(DEAD, DEAD), (ALIVE, ALIVE)]);
+    life.root = copy_node(life, &old_nodes, &mut remap, life.root);
+
+    let results = std::mem::take(&mut life.results);
+    for ((id, step_log2), result) in results {
+        if let (Some(id), Some(result)) = (remap.get(&id), remap.get(&result)) {
+            life.results.insert((*id, step_log2), *result);
+        }
+    }
+}
+
+fn copy_node(life: &mut HashLife, old_nodes: &[Node], remap: &mut HashMap<NodeId, NodeId>, id: NodeId) -> NodeId {
+    if let Some(copied) = remap.get(&id) {
+        return *copied;
+    }
+    let old_children = old_nodes[id.0 as usize].children;
+    let mut children = [DEAD; 4];
+    for (index, old_child) in old_children.iter().enumerate() {
+        children[index] = copy_node(life, old_nodes, remap, *old_child);
+    }
+    let copied = join(life, children);
+    remap.insert(id, copied);
+    copied
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{conway_rule, parse_rule, seed_soup, LifeBackend};
+
+    const GLIDER: [GridCoordinate; 5] = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+    const GOSPER_GLIDER_GUN: [&str; 9] = [
+        "........................O...........",
+        "......................O.O...........",
+        "............OO......OO............OO",
+        "...........O...O....OO............OO",
+        "OO........O.....O...OO..............",
+        "OO........O...O.OO....O.O...........",
+        "..........O.....O.......O...........",
+        "...........O...O....................",
+        "............OO......................",
+    ];
+
+    fn create_glider() -> HashLife {
+        let mut life = hashlife(conway_rule(), Neighbourhood::Moore).unwrap();
+        for coordinate in GLIDER {
+            set_hashlife_cell(&mut life, coordinate, true).unwrap();
+        }
+        life
+    }
+
+    #[test]
+    fn glider_reaches_generation_one_billion() {
+        let mut life = create_glider();
+        let generations = 1_000_000_000u64;
+        step_hashlife(&mut life, generations).unwrap();
+
+        let offset = (generations / 4) as i64;
+        let mut expected: Vec<GridCoordinate> = GLIDER.iter().map(|(x, y)| (x + offset, y + offset)).collect();
+        expected.sort_by_key(|(x, y)| (*y, *x));
+        assert_eq!(hashlife_live_cells(&life), expected);
+        assert_eq!(hashlife_generation(&life), generations);
+    }
+
+    #[test]
+    fn glider_gun_runs_headless_to_one_billion() {
+        let mut life = hashlife(conway_rule(), Neighbourhood::Moore).unwrap();
+        for (y, row) in GOSPER_GLIDER_GUN.iter().enumerate() {
+            for (x, cell) in row.chars().enumerate() {
+                set_hashlife_cell(&mut life, (x as i64, y as i64), cell == 'O').unwrap();
+            }
+        }
+        step_hashlife(&mut life, 1_000_000_000).unwrap();
+
+        let gliders = 1_000_000_000 / 30;
+        let population = hashlife_population(&life);
+        assert!((5 * (gliders - 2)..5 * gliders + 50).contains(&population), "population {}", population);
+    }
+
+    #[test]
+    fn large_steps_match_single_steps_after_garbage_collection() {
+        let rule = parse_rule("B36/S23").unwrap();
+        let mut single = hashlife(rule, Neighbourhood::Moore).unwrap();
+        let mut large = hashlife(rule, Neighbourhood::Moore).unwrap();
+        seed_soup(&mut single, 0..16, 0..16, 0x2545_f491_4f6c_dd1d);
+        seed_soup(&mut large, 0..16, 0..16, 0x2545_f491_4f6c_dd1d);
+        set_hashlife_gc_threshold(&mut single, 64);
+
+        for _ in 0..64 {
+            step_hashlife(&mut single, 1).unwrap();
+        }
+        step_hashlife_pow2(&mut large, 6).unwrap();
+        assert_eq!(hashlife_live_cells(&single), hashlife_live_cells(&large));
+        assert_eq!(hashlife_population(&single), hashlife_population(&large));
+    }
+
+    #[test]
+    fn rules_with_birth_on_zero_are_rejected() {
+        let rule = parse_rule("B0/S8").unwrap();
+        assert_eq!(hashlife(rule, Neighbourhood::Moore).err(), Some(HashLifeError::BirthOnZero(rule)));
+    }
+
+    #[test]
+    fn oversized_steps_are_rejected() {
+        let mut life = create_glider();
+        assert_eq!(step_hashlife(&mut life, 1 << 61), Err(HashLifeError::StepTooLarge { step_log2: 61 }));
+        assert_eq!(step_hashlife(&mut life, u64::MAX), Err(HashLifeError::StepTooLarge { step_log2: 63 }));
+        assert_eq!(step_hashlife_pow2(&mut life, 200), Err(HashLifeError::StepTooLarge { step_log2: 200 }));
+        assert_eq!(hashlife_generation(&life), 0);
+
+        step_hashlife(&mut life, 1 << MAX_STEP_LOG2).unwrap();
+        assert_eq!(hashlife_population(&life), 5);
+    }
+
+    #[test]
+    fn cells_beyond_the_largest_universe_are_rejected() {
+        let mut life = create_glider();
+        assert_eq!(set_hashlife_cell(&mut life, (i64::MAX / 2, 0), true), Err(HashLifeError::UniverseTooLarge));
+        assert!(!life.set_cell((i64::MIN, 0), true));
+        assert!(life.set_cell((1 << 60, 0), true));
+        assert_eq!(hashlife_population(&life), 6);
+    }
+}
@@ -1,27 +1,52 @@
 mod math;
-mod ecs;
-mod systems;
+pub mod ecs;
+pub mod systems;
+mod neighbourhood;
+mod spatial;
+mod tiles;
+mod rule;
+mod topology;
+mod render;
+mod bitgrid;
+mod backend;
+mod hashlife;
+
+use std::{
+    fmt,
+    sync::Arc
+};
 
 use gol_engine::*;
 use ecs::*;
 use math::*;
 use systems::run_systems;
 
+pub use neighbourhood::*;
+pub use spatial::*;
+pub use tiles::*;
+pub use rule::*;
+pub use topology::*;
+pub use render::*;
+pub use bitgrid::*;
+pub use backend::*;
+pub use hashlife::*;
+
 #[no_mangle]
 pub extern "C" fn game_update_and_render(
-    _thread_context: &mut ThreadContext,
+    thread_context: &mut ThreadContext,
     game_memory: &mut GameMemory, 
-    _game_input: &mut GameInput, 
-    _buffer: &mut GameOffscreenBuffer
+    game_input: &mut GameInput, 
+    buffer: &mut GameOffscreenBuffer
 ) {
     if !initialised(game_memory) {    
-        let mut world = Box::new(create_world());
-        set_game_memory_root(game_memory, world.as_mut());
-        add_initial_entities_to_world(get_game_memory_root(game_memory));
+        let world = Box::leak(Box::new(create_initial_world(thread_context, game_memory)));
+        set_game_memory_root(game_memory, world);
         mark_as_initialised(game_memory);
     }
     
-    run_systems(get_game_memory_root(game_memory));
+    let world: &mut World = get_game_memory_root(game_memory);
+    run_systems(thread_context, world, game_input.delta_time_for_frame);
+    render_board(world, buffer);
 
 }
 
@@ -33,27 +58,205 @@ pub extern "C" fn get_game_sound_samples(
 ) {
 }
 
-fn add_initial_entities_to_world(world: &mut World) {
-    let cell_1 = add_entity(world);
-    let cell_2 = add_entity(world);
-    let cell_3 = add_entity(world);
-    add_component(world, cell_1, CellNeighbours { north: None, east: Some(cell_2), south: Some(cell_3), west: None });
-    add_component(world, cell_1, CellPosition(v2(0.0, 0.0)));
+const INITIAL_SCENE_FILE_NAME: &str = "initial.scene";
+const DEFAULT_INITIAL_SCENE: &str = include_str!("../../data/initial.scene");
+
+fn create_initial_world(thread_context: &mut ThreadContext, game_memory: &GameMemory) -> World {
+    let registry = Arc::new(create_game_component_registry());
     
-    add_component(world, cell_2, CellNeighbours{ north: None, east: None, south: None, west: Some(cell_1) });
-    add_component(world, cell_2, CellPosition(v2(1.0, 0.0)));
+    if let Some(scene) = read_data_file_text(thread_context, game_memory, INITIAL_SCENE_FILE_NAME) {
+        let mut world = create_world_with_registry(registry.clone());
+        match load_board_scene(&mut world, &scene) {
+            Ok(_) => return world,
+            Err(error) => game_memory.debug_platform_log_message(thread_context, &format!("{}: {}", INITIAL_SCENE_FILE_NAME, error))
+        }
+    }
+
+    let mut world = create_world_with_registry(registry);
+    load_board_scene(&mut world, DEFAULT_INITIAL_SCENE).unwrap();
+    world
+}
 
-    add_component(world, cell_3, CellNeighbours{ north: Some(cell_1), east: None, south: None, west: None });
-    add_component(world, cell_3, CellPosition(v2(0.0, 1.0)));
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BoardSceneError {
+    Scene(SceneError),
+    SpatialIndex(SpatialIndexError),
 }
 
-#[derive(Debug)]
+impl fmt::Display for BoardSceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BoardSceneError::Scene(error) => write!(f, "{}", error),
+            BoardSceneError::SpatialIndex(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+pub fn load_board_scene(world: &mut World, scene: &str) -> Result<SceneEntities, BoardSceneError> {
+    let entities = load_scene(world, scene).map_err(BoardSceneError::Scene)?;
+    rebuild_spatial_index(world).map_err(BoardSceneError::SpatialIndex)?;
+    fill_bounded_board(world);
+    systems::maintain_frontier(world);
+    Ok(entities)
+}
+
+fn read_data_file_text(thread_context: &mut ThreadContext, game_memory: &GameMemory, file_name: &str) -> Option<String> {
+    let file = game_memory.debug_platform_read_entire_file(thread_context, file_name)?;
+    let bytes = unsafe { std::slice::from_raw_parts(file.contents as *const u8, file.contents_size as usize) };
+    let text = String::from_utf8_lossy(bytes).into_owned();
+    game_memory.debug_platform_free_file_memory(thread_context, file.contents);
+    Some(text)
+}
+
+pub fn create_game_component_registry() -> ComponentRegistry {
+    let mut registry = component_registry();
+    register_hierarchy_components(&mut registry);
+    register_rule_resource(&mut registry);
+    register_topology_resource(&mut registry);
+    register_tile_component(&mut registry);
+    register_component::<CellPosition>(&mut registry, "CellPosition");
+    register_component_serialization(&mut registry, save_cell_position, load_cell_position);
+    register_component_scene_parser(&mut registry, parse_cell_position);
+    register_component_debug::<CellPosition>(&mut registry);
+    register_component_copy::<CellPosition>(&mut registry);
+    register_spatial_index(&mut registry);
+    register_component::<CellNeighbours>(&mut registry, "CellNeighbours");
+    register_component_serialization(&mut registry, save_cell_neighbours, load_cell_neighbours);
+    register_component_entity_references(&mut registry, map_cell_neighbours);
+    register_component_scene_parser(&mut registry, parse_cell_neighbours);
+    register_component_debug::<CellNeighbours>(&mut registry);
+    register_component_copy::<CellNeighbours>(&mut registry);
+    register_component::<CellState>(&mut registry, "CellState");
+    register_component_serialization(&mut registry, save_cell_state, load_cell_state);
+    register_component_scene_parser(&mut registry, parse_cell_state);
+    register_component_debug::<CellState>(&mut registry);
+    register_component_copy::<CellState>(&mut registry);
+    registry
+}
+
+pub fn create_cell_grid(world: &mut World, width: usize, height: usize) -> Vec<Entity> {
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x as i64, y as i64)))
+        .filter_map(|coordinate| spawn_cell(world, coordinate, false))
+        .collect()
+}
+
+#[derive(Debug, Copy, Clone)]
 pub struct CellPosition(V2);
 
-#[derive(Debug)]
-pub struct CellNeighbours {
-    pub north: Option<Entity>,
-    pub east: Option<Entity>,
-    pub south: Option<Entity>,
-    pub west: Option<Entity>,
-}
\ No newline at end of file
+fn save_cell_position(position: &CellPosition, writer: &mut SnapshotWriter) {
+    writer.write_f32(position.0.x);
+    writer.write_f32(position.0.y);
+}
+
+fn load_cell_position(reader: &mut SnapshotReader) -> Result<CellPosition, SnapshotError> {
+    Ok(CellPosition(v2(reader.read_f32()?, reader.read_f32()?)))
+}
+
+fn parse_cell_position(fields: &SceneFields) -> Result<CellPosition, SceneErrorKind> {
+    Ok(CellPosition(v2(fields.get("x")?, fields.get("y")?)))
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CellState {
+    pub alive: bool,
+}
+
+fn save_cell_state(state: &CellState, writer: &mut SnapshotWriter) {
+    writer.write_bool(state.alive);
+}
+
+fn load_cell_state(reader: &mut SnapshotReader) -> Result<CellState, SnapshotError> {
+    Ok(CellState { alive: reader.read_bool()? })
+}
+
+fn parse_cell_state(fields: &SceneFields) -> Result<CellState, SceneErrorKind> {
+    Ok(CellState { alive: fields.get_or("alive", false)? })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn default_initial_scene_loads() {
+        let mut world = create_world_with_registry(Arc::new(create_game_component_registry()));
+        let entities = load_board_scene(&mut world, DEFAULT_INITIAL_SCENE).unwrap();
+        let cell_1 = get_scene_entity(&entities, "cell_1").unwrap();
+        let cell_2 = get_scene_entity(&entities, "cell_2").unwrap();
+        let cell_3 = get_scene_entity(&entities, "cell_3").unwrap();
+
+        let neighbours = get_component::<CellNeighbours>(&world, cell_1).unwrap();
+        assert_eq!(get_neighbour(neighbours, Direction::East), Some(cell_2));
+        assert_eq!(get_neighbour(neighbours, Direction::South), Some(cell_3));
+        assert_eq!(get_neighbour(get_component::<CellNeighbours>(&world, cell_3).unwrap(), Direction::North), Some(cell_1));
+        assert_eq!(get_component::<CellPosition>(&world, cell_2).unwrap().0, v2(1.0, 0.0));
+    }
+
+    #[test]
+    fn spatial_index_survives_despawn_clone_and_save() {
+        let registry = Arc::new(create_game_component_registry());
+        let mut world = create_world_with_registry(registry.clone());
+        let entities = load_board_scene(&mut world, DEFAULT_INITIAL_SCENE).unwrap();
+        let cell_1 = get_scene_entity(&entities, "cell_1").unwrap();
+        let cell_2 = get_scene_entity(&entities, "cell_2").unwrap();
+        let cell_count = spatial_index_len(&world);
+
+        despawn(&mut world, cell_2);
+        assert_eq!(get_cell_at(&world, (1, 0)), None);
+        assert_eq!(get_neighbour(get_component::<CellNeighbours>(&world, cell_1).unwrap(), Direction::East), None);
+        assert_eq!(spatial_index_len(&world), cell_count - 1);
+
+        let cloned = clone_world(&world);
+        assert_eq!(get_cell_at(&cloned, (0, 0)), Some(cell_1));
+        assert_eq!(spatial_index_len(&cloned), cell_count - 1);
+
+        let loaded = load_world(&save_world(&world).unwrap(), registry).unwrap();
+        let loaded_cell = get_cell_at(&loaded, (0, 1)).unwrap();
+        assert_eq!(get_component::<CellPosition>(&loaded, loaded_cell).map(grid_coordinate), Some((0, 1)));
+        assert_eq!(spatial_index_len(&loaded), cell_count - 1);
+    }
+
+    #[test]
+    fn transferred_cells_move_between_spatial_indexes() {
+        let registry = Arc::new(create_game_component_registry());
+        let mut source = create_world_with_registry(registry.clone());
+        let mut target = create_world_with_registry(registry);
+        let cell = spawn_cell(&mut source, (0, 0), true).unwrap();
+        let kept = spawn_cell(&mut target, (0, 0), false).unwrap();
+
+        let moved = move_entity_to_world(&mut source, &mut target, cell).unwrap();
+        assert_eq!(get_cell_at(&source, (0, 0)), None);
+        assert_eq!(spatial_index_len(&source), 0);
+        assert_eq!(get_cell_at(&target, (0, 0)), Some(moved));
+        assert!(get_component::<CellPosition>(&target, kept).is_none());
+        assert_ne!(spawn_cell(&mut source, (0, 0), true), Some(cell));
+
+        let copied = copy_entity_to_world(&target, &mut source, moved).unwrap();
+        assert_eq!(get_cell_at(&source, (0, 0)), Some(copied));
+        assert_eq!(spatial_index_len(&source), 1);
+        assert_eq!(get_cell_at(&target, (0, 0)), Some(moved));
+    }
+
+    #[test]
+    fn cells_sharing_a_coordinate_are_rejected() {
+        let mut world = create_world_with_registry(Arc::new(create_game_component_registry()));
+        let scene = "entity a\n    CellPosition x=2 y=3\nentity b\n    CellPosition x=2 y=3\n";
+        let error = load_board_scene(&mut world, scene).err().unwrap();
+        assert!(matches!(error, BoardSceneError::SpatialIndex(SpatialIndexError::DuplicateCoordinate { coordinate: (2, 3), .. })));
+    }
+
+    #[test]
+    fn scenes_set_the_rule_and_topology() {
+        let registry = Arc::new(create_game_component_registry());
+        let mut world = create_world_with_registry(registry.clone());
+        let scene = "resource Rule rule=B36/S23:T4,4\nentity a\n    CellPosition x=5 y=1\n";
+        let a = get_scene_entity(&load_board_scene(&mut world, scene).unwrap(), "a").unwrap();
+        assert_eq!(get_cell_at(&world, (1, 1)), Some(a));
+        assert_eq!(spatial_index_len(&world), 16);
+
+        for world in [clone_world(&world), load_world(&save_world(&world).unwrap(), registry).unwrap()] {
+            assert_eq!(format_rule_with_topology(&get_world_rule(&world), &get_world_topology(&world)), "B36/S23:T4,4");
+        }
+    }
+}
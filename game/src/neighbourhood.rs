@@ -0,0 +1,151 @@
+use crate::ecs::*;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+pub const MOORE_DIRECTIONS: [Direction; 8] = [
+    Direction::North,
+    Direction::NorthEast,
+    Direction::East,
+    Direction::SouthEast,
+    Direction::South,
+    Direction::SouthWest,
+    Direction::West,
+    Direction::NorthWest,
+];
+
+pub const VON_NEUMANN_DIRECTIONS: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+pub fn direction_offset(direction: Direction) -> (i64, i64) {
+    match direction {
+        Direction::North => (0, -1),
+        Direction::NorthEast => (1, -1),
+        Direction::East => (1, 0),
+        Direction::SouthEast => (1, 1),
+        Direction::South => (0, 1),
+        Direction::SouthWest => (-1, 1),
+        Direction::West => (-1, 0),
+        Direction::NorthWest => (-1, -1),
+    }
+}
+
+pub fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::North => "north",
+        Direction::NorthEast => "north_east",
+        Direction::East => "east",
+        Direction::SouthEast => "south_east",
+        Direction::South => "south",
+        Direction::SouthWest => "south_west",
+        Direction::West => "west",
+        Direction::NorthWest => "north_west",
+    }
+}
+
+pub fn opposite_direction(direction: Direction) -> Direction {
+    MOORE_DIRECTIONS[(direction as usize + 4) % MOORE_DIRECTIONS.len()]
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Neighbourhood {
+    #[default]
+    Moore,
+    VonNeumann,
+}
+
+pub fn neighbourhood_directions(neighbourhood: Neighbourhood) -> &'static [Direction] {
+    match neighbourhood {
+        Neighbourhood::Moore => &MOORE_DIRECTIONS,
+        Neighbourhood::VonNeumann => &VON_NEUMANN_DIRECTIONS,
+    }
+}
+
+pub fn get_world_neighbourhood(world: &World) -> Neighbourhood {
+    get_resource::<Neighbourhood>(world).copied().unwrap_or_default()
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CellNeighbours {
+    links: [Option<Entity>; 8],
+}
+
+pub fn cell_neighbours(links: impl IntoIterator<Item = (Direction, Entity)>) -> CellNeighbours {
+    let mut neighbours = CellNeighbours::default();
+    for (direction, neighbour) in links {
+        set_neighbour(&mut neighbours, direction, Some(neighbour));
+    }
+    neighbours
+}
+
+pub fn get_neighbour(neighbours: &CellNeighbours, direction: Direction) -> Option<Entity> {
+    neighbours.links[direction as usize]
+}
+
+pub fn set_neighbour(neighbours: &mut CellNeighbours, direction: Direction, neighbour: Option<Entity>) {
+    neighbours.links[direction as usize] = neighbour;
+}
+
+pub fn neighbour_iter(neighbours: &CellNeighbours, neighbourhood: Neighbourhood) -> impl Iterator<Item = Entity> + '_ {
+    neighbourhood_directions(neighbourhood)
+        .iter()
+        .filter_map(|direction| get_neighbour(neighbours, *direction))
+}
+
+pub fn save_cell_neighbours(neighbours: &CellNeighbours, writer: &mut SnapshotWriter) {
+    for link in &neighbours.links {
+        writer.write_optional_entity(*link);
+    }
+}
+
+pub fn load_cell_neighbours(reader: &mut SnapshotReader) -> Result<CellNeighbours, SnapshotError> {
+    let mut neighbours = CellNeighbours::default();
+    for link in &mut neighbours.links {
+        *link = reader.read_optional_entity()?;
+    }
+    Ok(neighbours)
+}
+
+pub fn map_cell_neighbours(neighbours: &mut CellNeighbours, map: &dyn Fn(Entity) -> Entity) {
+    for link in &mut neighbours.links {
+        *link = link.map(map);
+    }
+}
+
+pub fn parse_cell_neighbours(fields: &SceneFields) -> Result<CellNeighbours, SceneErrorKind> {
+    let mut neighbours = CellNeighbours::default();
+    for direction in MOORE_DIRECTIONS {
+        set_neighbour(&mut neighbours, direction, fields.get_or(direction_name(direction), None)?);
+    }
+    Ok(neighbours)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn neighbourhoods_select_their_directions() {
+        let mut world = create_world();
+        let cells: Vec<Entity> = (0..8).map(|_| add_entity(&mut world)).collect();
+        let neighbours = cell_neighbours(MOORE_DIRECTIONS.iter().copied().zip(cells.iter().copied()));
+
+        assert_eq!(neighbour_iter(&neighbours, Neighbourhood::Moore).collect::<Vec<_>>(), cells);
+        assert_eq!(
+            neighbour_iter(&neighbours, Neighbourhood::VonNeumann).collect::<Vec<_>>(),
+            vec!(cells[0], cells[2], cells[4], cells[6])
+        );
+        for direction in MOORE_DIRECTIONS {
+            let (x, y) = direction_offset(direction);
+            assert_eq!(direction_offset(opposite_direction(direction)), (-x, -y));
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use gol_engine::GameOffscreenBuffer;
+
+use crate::{ecs::*, systems::is_cell_alive, get_cell_at, get_world_topology, hashlife_range_population, resolve_coordinate, GridCoordinate, HashLife};
+
+pub const LIVE_CELL_COLOUR: u32 = 0x00ff_ffff;
+pub const DEAD_CELL_COLOUR: u32 = 0x0020_2020;
+pub const OUTSIDE_BOARD_COLOUR: u32 = 0x0000_0000;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Camera {
+    pub x: f64,
+    pub y: f64,
+    pub cell_pixels: f64,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera { x: 0.0, y: 0.0, cell_pixels: 8.0 }
+    }
+}
+
+pub fn render_board(world: &World, buffer: &mut GameOffscreenBuffer) {
+    let camera = get_resource::<Camera>(world).copied().unwrap_or_default();
+    let topology = get_world_topology(world);
+    let cell_colour = |coordinate: GridCoordinate, _| match resolve_coordinate(&topology, coordinate) {
+        Some(coordinate) if get_cell_at(world, coordinate).is_some_and(|cell| is_cell_alive(world, cell)) => LIVE_CELL_COLOUR,
+        Some(_) => DEAD_CELL_COLOUR,
+        None => OUTSIDE_BOARD_COLOUR,
+    };
+
+    fill_buffer(buffer, &camera, cell_colour);
+}
+
+pub fn render_hashlife(life: &HashLife, camera: &Camera, buffer: &mut GameOffscreenBuffer) {
+    fill_buffer(buffer, camera, |min, max| {
+        if hashlife_range_population(life, min, max) > 0 { LIVE_CELL_COLOUR } else { DEAD_CELL_COLOUR }
+    });
+}
+
+fn pixel_cells(origin: f64, pixel: u32, cell_pixels: f64) -> (i64, i64) {
+    let min = (origin + pixel as f64 / cell_pixels).floor() as i64;
+    let max = (origin + (pixel + 1) as f64 / cell_pixels).ceil() as i64;
+    (min, max.max(min + 1))
+}
+
+fn fill_buffer(buffer: &mut GameOffscreenBuffer, camera: &Camera, mut cell_colour: impl FnMut(GridCoordinate, GridCoordinate) -> u32) {
+    let mut last = None;
+    for y in 0..buffer.height {
+        let (min_y, max_y) = pixel_cells(camera.y, y, camera.cell_pixels);
+        let row = unsafe { (buffer.memory as *mut u8).add((y * buffer.pitch) as usize) as *mut u32 };
+        for x in 0..buffer.width {
+            let (min_x, max_x) = pixel_cells(camera.x, x, camera.cell_pixels);
+            let cells = ((min_x, min_y), (max_x, max_y));
+            let colour = match last {
+                Some((last_cells, colour)) if last_cells == cells => colour,
+                _ => cell_colour(cells.0, cells.1),
+            };
+            last = Some((cells, colour));
+            unsafe {
+                *row.add(x as usize) = colour;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{conway_rule, hashlife, set_hashlife_cell, spawn_cell, Neighbourhood, Topology};
+
+    fn render_pixels(world: &World, width: u32, height: u32) -> Vec<u32> {
+        let mut pixels = vec!(0xdead_beefu32; (width * height) as usize);
+        let mut buffer = GameOffscreenBuffer {
+            memory: pixels.as_mut_ptr() as *mut std::ffi::c_void,
+            width,
+            height,
+            pitch: width * 4,
+            bytes_per_pixel: 4,
+        };
+        render_board(world, &mut buffer);
+        pixels
+    }
+
+    fn render_hashlife_pixels(life: &HashLife, camera: Camera, width: u32, height: u32) -> Vec<u32> {
+        let mut pixels = vec!(0xdead_beefu32; (width * height) as usize);
+        let mut buffer = GameOffscreenBuffer {
+            memory: pixels.as_mut_ptr() as *mut std::ffi::c_void,
+            width,
+            height,
+            pitch: width * 4,
+            bytes_per_pixel: 4,
+        };
+        render_hashlife(life, &camera, &mut buffer);
+        pixels
+    }
+
+    #[test]
+    fn rendering_follows_topology() {
+        let mut torus = create_world();
+        add_resource(&mut torus, Topology::Torus { width: 2, height: 2 });
+        add_resource(&mut torus, Camera { x: 0.0, y: 0.0, cell_pixels: 1.0 });
+        spawn_cell(&mut torus, (0, 0), true);
+        spawn_cell(&mut torus, (1, 1), false);
+        let (l, d) = (LIVE_CELL_COLOUR, DEAD_CELL_COLOUR);
+        assert_eq!(render_pixels(&torus, 3, 3), vec!(l, d, l, d, d, d, l, d, l));
+
+        let mut plane = create_world();
+        add_resource(&mut plane, Topology::Plane { width: 1, height: 1 });
+        add_resource(&mut plane, Camera { x: -1.0, y: 0.0, cell_pixels: 2.0 });
+        spawn_cell(&mut plane, (0, 0), true);
+        let o = OUTSIDE_BOARD_COLOUR;
+        assert_eq!(render_pixels(&plane, 4, 3), vec!(o, o, l, l, o, o, l, l, o, o, o, o));
+    }
+
+    #[test]
+    fn hashlife_renders_at_any_zoom() {
+        let mut life = hashlife(conway_rule(), Neighbourhood::Moore).unwrap();
+        set_hashlife_cell(&mut life, (1, 0), true).unwrap();
+        set_hashlife_cell(&mut life, (1_500_000, 2_500_000), true).unwrap();
+        let (l, d) = (LIVE_CELL_COLOUR, DEAD_CELL_COLOUR);
+
+        let close = Camera { x: 0.0, y: 0.0, cell_pixels: 1.0 };
+        assert_eq!(render_hashlife_pixels(&life, close, 3, 1), vec!(d, l, d));
+
+        let far = Camera { x: 0.0, y: 0.0, cell_pixels: 1.0 / 1_048_576.0 };
+        assert_eq!(render_hashlife_pixels(&life, far, 2, 3), vec!(l, d, d, d, d, l));
+
+        set_hashlife_cell(&mut life, (4, 0), true).unwrap();
+        set_hashlife_cell(&mut life, (1, 0), false).unwrap();
+        let unaligned = Camera { x: 1.0, y: 0.0, cell_pixels: 0.25 };
+        assert_eq!(render_hashlife_pixels(&life, unaligned, 2, 1), vec!(l, d));
+
+        set_hashlife_cell(&mut life, (250_000_001, 0), true).unwrap();
+        let distant = Camera { x: 250_000_000.0, y: 0.0, cell_pixels: 4.0 };
+        assert_eq!(render_hashlife_pixels(&life, distant, 12, 1), vec!(d, d, d, d, l, l, l, l, d, d, d, d));
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    fmt,
+    str::FromStr
+};
+
+use crate::{ecs::*, Topology, TopologyErrorKind, parse_topology};
+
+pub const MAX_NEIGHBOUR_COUNT: usize = 8;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rule {
+    birth: u16,
+    survival: u16,
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        conway_rule()
+    }
+}
+
+pub fn conway_rule() -> Rule {
+    rule(&[3], &[2, 3])
+}
+
+pub fn rule(birth: &[usize], survival: &[usize]) -> Rule {
+    let mask = |counts: &[usize]| counts
+        .iter()
+        .filter(|count| **count <= MAX_NEIGHBOUR_COUNT)
+        .fold(0u16, |mask, count| mask | 1 << count);
+    Rule { birth: mask(birth), survival: mask(survival) }
+}
+
+pub fn rule_births(rule: &Rule, live_neighbours: usize) -> bool {
+    live_neighbours <= MAX_NEIGHBOUR_COUNT && rule.birth & (1 << live_neighbours) != 0
+}
+
+pub fn rule_survives(rule: &Rule, live_neighbours: usize) -> bool {
+    live_neighbours <= MAX_NEIGHBOUR_COUNT && rule.survival & (1 << live_neighbours) != 0
+}
+
+pub fn next_cell_state(rule: &Rule, alive: bool, live_neighbours: usize) -> bool {
+    if alive {
+        rule_survives(rule, live_neighbours)
+    } else {
+        rule_births(rule, live_neighbours)
+    }
+}
+
+pub fn get_world_rule(world: &World) -> Rule {
+    get_resource::<Rule>(world).copied().unwrap_or_default()
+}
+
+pub fn register_rule_resource(registry: &mut ComponentRegistry) {
+    register_resource::<Rule>(registry, "Rule", save_rule, load_rule);
+    register_resource_clone::<Rule>(registry);
+    register_resource_scene_parser::<Rule>(registry, parse_rule_resource);
+}
+
+fn parse_rule_resource(world: &mut World, fields: &SceneFields) -> Result<(), SceneErrorKind> {
+    let text: String = fields.get("rule")?;
+    let (rule, topology) = parse_rule_with_topology(&text).map_err(|_| SceneErrorKind::InvalidValue {
+        field: "rule".to_string(),
+        value: text.clone(),
+        expected: "a rulestring such as B3/S23:T100,80"
+    })?;
+    add_resource(world, rule);
+    add_resource(world, topology);
+    Ok(())
+}
+
+fn save_rule(rule: &Rule, writer: &mut SnapshotWriter) {
+    writer.write_str(&rule.to_string());
+}
+
+fn load_rule(reader: &mut SnapshotReader) -> Result<Rule, SnapshotError> {
+    parse_rule(reader.read_str()?).map_err(|_| SnapshotError::InvalidValue("Rule"))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleErrorKind {
+    Empty,
+    MissingSeparator,
+    TooManySeparators,
+    UnexpectedCharacter(char),
+    NeighbourCountOutOfRange(char),
+    DuplicateNeighbourCount(char),
+    DuplicateSection(char),
+    MixedNotation,
+    Topology(TopologyErrorKind),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleError {
+    pub column: usize,
+    pub kind: RuleErrorKind,
+}
+
+fn rule_error(column: usize, kind: RuleErrorKind) -> RuleError {
+    RuleError { column, kind }
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: ", self.column)?;
+        match &self.kind {
+            RuleErrorKind::Empty => write!(f, "rulestring is empty"),
+            RuleErrorKind::MissingSeparator => write!(f, "expected '/' between birth and survival counts"),
+            RuleErrorKind::TooManySeparators => write!(f, "rulestring has more than one '/'"),
+            RuleErrorKind::UnexpectedCharacter(character) => write!(f, "unexpected character '{}'", character),
+            RuleErrorKind::NeighbourCountOutOfRange(character) => write!(f, "neighbour count '{}' is greater than {}", character, MAX_NEIGHBOUR_COUNT),
+            RuleErrorKind::DuplicateNeighbourCount(character) => write!(f, "neighbour count '{}' is listed twice", character),
+            RuleErrorKind::DuplicateSection(section) => write!(f, "section '{}' is given twice", section),
+            RuleErrorKind::MixedNotation => write!(f, "cannot mix B/S prefixes with the unprefixed S/B form"),
+            RuleErrorKind::Topology(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = |mask: u16| (0..=MAX_NEIGHBOUR_COUNT)
+            .filter(|count| mask & (1 << count) != 0)
+            .map(|count| count.to_string())
+            .collect::<String>();
+        write!(f, "B{}/S{}", digits(self.birth), digits(self.survival))
+    }
+}
+
+impl FromStr for Rule {
+    type Err = RuleError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        parse_rule(text)
+    }
+}
+
+pub fn parse_rule_with_topology(text: &str) -> Result<(Rule, Topology), RuleError> {
+    let (rule_text, topology_text) = match text.split_once(':') {
+        Some((rule_text, topology_text)) => (rule_text, Some(topology_text)),
+        None => (text, None),
+    };
+    let rule = parse_rule(rule_text)?;
+    let topology = match topology_text {
+        Some(topology_text) => parse_topology(topology_text).map_err(|error| {
+            rule_error(rule_text.chars().count() + 1 + error.column, RuleErrorKind::Topology(error.kind))
+        })?,
+        None => Topology::Unbounded,
+    };
+    Ok((rule, topology))
+}
+
+pub fn format_rule_with_topology(rule: &Rule, topology: &Topology) -> String {
+    format!("{}{}", rule, topology)
+}
+
+pub fn parse_rule(text: &str) -> Result<Rule, RuleError> {
+    if text.trim().is_empty() {
+        return Err(rule_error(1, RuleErrorKind::Empty));
+    }
+
+    let mut sections = Vec::<(usize, &str)>::default();
+    let mut start = 0;
+    for (index, character) in text.char_indices() {
+        if character == '/' {
+            sections.push((start, &text[start..index]));
+            start = index + 1;
+        }
+    }
+    sections.push((start, &text[start..]));
+    match sections.len() {
+        1 => {
+            let prefixed = text.starts_with(['B', 'b', 'S', 's']) as usize;
+            parse_neighbour_counts(text, prefixed, &text[prefixed..])?;
+            return Err(rule_error(text.chars().count() + 1, RuleErrorKind::MissingSeparator));
+        },
+        2 => {},
+        _ => return Err(rule_error(column_of(text, sections[2].0 - 1), RuleErrorKind::TooManySeparators)),
+    }
+
+    let prefixes: Vec<Option<char>> = sections
+        .iter()
+        .map(|(_, section)| section.chars().next().map(|prefix| prefix.to_ascii_uppercase()).filter(|prefix| *prefix == 'B' || *prefix == 'S'))
+        .collect();
+    let mut birth = None;
+    let mut survival = None;
+    match (prefixes[0], prefixes[1]) {
+        (None, None) => {
+            survival = Some(parse_neighbour_counts(text, sections[0].0, sections[0].1)?);
+            birth = Some(parse_neighbour_counts(text, sections[1].0, sections[1].1)?);
+        },
+        (Some(_), Some(_)) => {
+            for ((offset, section), prefix) in sections.iter().zip(prefixes.iter().flatten()) {
+                let target = if *prefix == 'B' { &mut birth } else { &mut survival };
+                if target.is_some() {
+                    return Err(rule_error(column_of(text, *offset), RuleErrorKind::DuplicateSection(*prefix)));
+                }
+                *target = Some(parse_neighbour_counts(text, offset + 1, &section[1..])?);
+            }
+        },
+        (None, Some(_)) => return Err(rule_error(column_of(text, sections[1].0), RuleErrorKind::MixedNotation)),
+        (Some(_), None) => return Err(rule_error(column_of(text, sections[1].0), RuleErrorKind::MixedNotation)),
+    }
+
+    Ok(Rule { birth: birth.unwrap(), survival: survival.unwrap() })
+}
+
+fn parse_neighbour_counts(text: &str, offset: usize, section: &str) -> Result<u16, RuleError> {
+    let mut mask = 0u16;
+    for (index, character) in section.char_indices() {
+        let column = column_of(text, offset + index);
+        let count = match character.to_digit(10) {
+            Some(count) => count as usize,
+            None => return Err(rule_error(column, RuleErrorKind::UnexpectedCharacter(character))),
+        };
+        if count > MAX_NEIGHBOUR_COUNT {
+            return Err(rule_error(column, RuleErrorKind::NeighbourCountOutOfRange(character)));
+        }
+        if mask & (1 << count) != 0 {
+            return Err(rule_error(column, RuleErrorKind::DuplicateNeighbourCount(character)));
+        }
+        mask |= 1 << count;
+    }
+    Ok(mask)
+}
+
+fn column_of(text: &str, byte_offset: usize) -> usize {
+    text[..byte_offset].chars().count() + 1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rulestrings_round_trip() {
+        for text in ["B3/S23", "B36/S23", "B3678/S34678", "B/S", "B012345678/S012345678"] {
+            assert_eq!(parse_rule(text).unwrap().to_string(), text);
+        }
+        assert_eq!(parse_rule("23/3"), Ok(conway_rule()));
+        assert_eq!(parse_rule("s23/b3"), Ok(conway_rule()));
+        assert_eq!("B36/S23".parse::<Rule>(), Ok(rule(&[3, 6], &[2, 3])));
+    }
+
+    #[test]
+    fn malformed_rulestrings_report_their_column() {
+        let error = |text: &str| parse_rule(text).unwrap_err();
+        assert_eq!(error(""), rule_error(1, RuleErrorKind::Empty));
+        assert_eq!(error("B3S23"), rule_error(3, RuleErrorKind::UnexpectedCharacter('S')));
+        assert_eq!(error("B3"), rule_error(3, RuleErrorKind::MissingSeparator));
+        assert_eq!(error("B3/S23/"), rule_error(7, RuleErrorKind::TooManySeparators));
+        assert_eq!(error("B39/S23"), rule_error(3, RuleErrorKind::NeighbourCountOutOfRange('9')));
+        assert_eq!(error("B33/S23"), rule_error(3, RuleErrorKind::DuplicateNeighbourCount('3')));
+        assert_eq!(error("B3/B2"), rule_error(4, RuleErrorKind::DuplicateSection('B')));
+        assert_eq!(error("B3/23"), rule_error(4, RuleErrorKind::MixedNotation));
+        assert_eq!(error("B3/S2x"), rule_error(6, RuleErrorKind::UnexpectedCharacter('x')));
+        assert_eq!(error("B3/S2x").to_string(), "column 6: unexpected character 'x'");
+    }
+
+    #[test]
+    fn rulestrings_carry_a_topology_suffix() {
+        let (rule, topology) = parse_rule_with_topology("B3/S23:T100,80").unwrap();
+        assert_eq!(rule, conway_rule());
+        assert_eq!(topology, Topology::Torus { width: 100, height: 80 });
+        assert_eq!(format_rule_with_topology(&rule, &topology), "B3/S23:T100,80");
+        assert_eq!(parse_rule_with_topology("B36/S23").unwrap().1, Topology::Unbounded);
+
+        let error = parse_rule_with_topology("B3/S23:T100,x").unwrap_err();
+        assert_eq!(error, rule_error(13, RuleErrorKind::Topology(TopologyErrorKind::InvalidSize("x".to_string()))));
+        assert_eq!(error.to_string(), "column 13: 'x' is not a positive size");
+    }
+
+    #[test]
+    fn rules_decide_next_state() {
+        let high_life = parse_rule("B36/S23").unwrap();
+        assert!(next_cell_state(&high_life, false, 6));
+        assert!(!next_cell_state(&conway_rule(), false, 6));
+        assert!(next_cell_state(&high_life, true, 2));
+        assert!(!next_cell_state(&high_life, true, 4));
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    collections::*,
+    fmt
+};
+
+use crate::{ecs::*, math::*, CellPosition, CellState};
+use crate::{neighbourhood::*, tiles::*, topology::*};
+
+pub type GridCoordinate = (i64, i64);
+
+#[derive(Debug, Clone, Default)]
+pub struct SpatialIndex {
+    cells: HashMap<GridCoordinate, Entity>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpatialIndexError {
+    DuplicateCoordinate { coordinate: GridCoordinate, cells: (Entity, Entity) },
+}
+
+impl fmt::Display for SpatialIndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpatialIndexError::DuplicateCoordinate { coordinate, cells } => write!(f, "{:?} and {:?} are both at {:?}", cells.0, cells.1, coordinate),
+        }
+    }
+}
+
+pub fn register_spatial_index(registry: &mut ComponentRegistry) {
+    register_component_despawn::<CellPosition>(registry, remove_despawned_cell);
+    register_component_arrival::<CellPosition>(registry, index_arrived_cell);
+    register_resource::<SpatialIndex>(registry, "SpatialIndex", save_spatial_index, load_spatial_index);
+    register_resource_entity_references(registry, map_spatial_index);
+    register_resource_clone::<SpatialIndex>(registry);
+}
+
+fn save_spatial_index(index: &SpatialIndex, writer: &mut SnapshotWriter) {
+    let mut cells: Vec<_> = index.cells.iter().collect();
+    cells.sort_by_key(|(coordinate, _)| **coordinate);
+    writer.write_u64(cells.len() as u64);
+    for (coordinate, cell) in cells {
+        writer.write_u64(coordinate.0 as u64);
+        writer.write_u64(coordinate.1 as u64);
+        writer.write_entity(*cell);
+    }
+}
+
+fn load_spatial_index(reader: &mut SnapshotReader) -> Result<SpatialIndex, SnapshotError> {
+    let mut index = SpatialIndex::default();
+    for _ in 0..reader.read_u64()? {
+        let coordinate = (reader.read_u64()? as i64, reader.read_u64()? as i64);
+        if index.cells.insert(coordinate, reader.read_entity()?).is_some() {
+            return Err(SnapshotError::InvalidValue("SpatialIndex"));
+        }
+    }
+    Ok(index)
+}
+
+fn map_spatial_index(index: &mut SpatialIndex, map: &dyn Fn(Entity) -> Entity) {
+    for cell in index.cells.values_mut() {
+        *cell = map(*cell);
+    }
+}
+
+fn remove_despawned_cell(world: &mut World, cell: Entity) {
+    if let Some(coordinate) = get_component::<CellPosition>(world, cell).map(grid_coordinate) {
+        remove_cell_from_spatial_index(world, cell, coordinate);
+    }
+}
+
+fn index_arrived_cell(world: &mut World, cell: Entity) {
+    let Some(coordinate) = get_component::<CellPosition>(world, cell).map(grid_coordinate) else {
+        return;
+    };
+    if let Some(existing) = get_cell_at(world, coordinate).filter(|existing| *existing != cell) {
+        despawn(world, existing);
+    }
+    add_cell_to_spatial_index(world, cell, coordinate);
+    wake_tiles_around(world, coordinate);
+}
+
+pub fn grid_coordinate(position: &CellPosition) -> GridCoordinate {
+    (position.0.x.round() as i64, position.0.y.round() as i64)
+}
+
+pub fn cell_position(coordinate: GridCoordinate) -> CellPosition {
+    CellPosition(v2(coordinate.0 as f32, coordinate.1 as f32))
+}
+
+pub fn offset_coordinate(coordinate: GridCoordinate, direction: Direction) -> GridCoordinate {
+    let (dx, dy) = direction_offset(direction);
+    (coordinate.0 + dx, coordinate.1 + dy)
+}
+
+pub fn get_cell_at(world: &World, coordinate: GridCoordinate) -> Option<Entity> {
+    get_resource::<SpatialIndex>(world)?.cells.get(&coordinate).copied()
+}
+
+pub fn spatial_index_len(world: &World) -> usize {
+    get_resource::<SpatialIndex>(world).map_or(0, |index| index.cells.len())
+}
+
+pub fn neighbour_coordinate(topology: &Topology, coordinate: GridCoordinate, direction: Direction) -> Option<GridCoordinate> {
+    resolve_coordinate(topology, offset_coordinate(coordinate, direction))
+}
+
+pub fn spawn_cell(world: &mut World, coordinate: GridCoordinate, alive: bool) -> Option<Entity> {
+    let coordinate = resolve_coordinate(&get_world_topology(world), coordinate)?;
+    if let Some(cell) = get_cell_at(world, coordinate) {
+        add_component(world, cell, CellState { alive });
+        wake_tiles_around(world, coordinate);
+        return Some(cell);
+    }
+
+    let cell = add_entity(world);
+    add_component(world, cell, cell_position(coordinate));
+    add_component(world, cell, CellState { alive });
+    set_shared_component(world, cell, tile_of(coordinate));
+    add_cell_to_spatial_index(world, cell, coordinate);
+    if alive {
+        wake_tiles_around(world, coordinate);
+    }
+    Some(cell)
+}
+
+pub fn fill_bounded_board(world: &mut World) {
+    let Some((width, height)) = topology_size(&get_world_topology(world)) else {
+        return;
+    };
+    for coordinate in (0..height).flat_map(|y| (0..width).map(move |x| (x, y))) {
+        if get_cell_at(world, coordinate).is_none() {
+            spawn_cell(world, coordinate, false);
+        }
+    }
+}
+
+pub fn despawn_cell(world: &mut World, cell: Entity) {
+    remove_despawned_cell(world, cell);
+    despawn(world, cell);
+}
+
+pub fn rebuild_spatial_index(world: &mut World) -> Result<(), SpatialIndexError> {
+    let topology = get_world_topology(world);
+    let cells: Vec<(Entity, GridCoordinate, Option<GridCoordinate>)> = iterate_component_entities::<CellPosition>(world)
+        .map(|(cell, position)| (cell, grid_coordinate(position), resolve_coordinate(&topology, grid_coordinate(position))))
+        .collect();
+
+    add_resource(world, SpatialIndex::default());
+    let mut placed = Vec::<(Entity, GridCoordinate)>::default();
+    for (cell, coordinate, resolved) in cells {
+        match resolved {
+            Some(resolved) => {
+                if resolved != coordinate {
+                    add_component(world, cell, cell_position(resolved));
+                }
+                set_shared_component(world, cell, tile_of(resolved));
+                if let Some(other) = get_spatial_index_mut(world).cells.insert(resolved, cell) {
+                    return Err(SpatialIndexError::DuplicateCoordinate { coordinate: resolved, cells: (other, cell) });
+                }
+                placed.push((cell, resolved));
+            },
+            None => despawn(world, cell),
+        }
+    }
+    for (cell, coordinate) in placed {
+        relink_cell(world, cell, coordinate);
+    }
+    wake_all_tiles(world);
+    Ok(())
+}
+
+fn add_cell_to_spatial_index(world: &mut World, cell: Entity, coordinate: GridCoordinate) {
+    get_spatial_index_mut(world).cells.insert(coordinate, cell);
+    relink_cell(world, cell, coordinate);
+    relink_neighbours(world, coordinate);
+}
+
+fn remove_cell_from_spatial_index(world: &mut World, cell: Entity, coordinate: GridCoordinate) {
+    if get_cell_at(world, coordinate) != Some(cell) {
+        return;
+    }
+    get_spatial_index_mut(world).cells.remove(&coordinate);
+    relink_neighbours(world, coordinate);
+}
+
+fn relink_neighbours(world: &mut World, coordinate: GridCoordinate) {
+    let topology = get_world_topology(world);
+    for direction in MOORE_DIRECTIONS {
+        let neighbour = neighbour_coordinate(&topology, coordinate, direction)
+            .and_then(|neighbour_coordinate| Some((get_cell_at(world, neighbour_coordinate)?, neighbour_coordinate)));
+        if let Some((neighbour, neighbour_coordinate)) = neighbour {
+            relink_cell(world, neighbour, neighbour_coordinate);
+        }
+    }
+}
+
+fn relink_cell(world: &mut World, cell: Entity, coordinate: GridCoordinate) {
+    let topology = get_world_topology(world);
+    let links = MOORE_DIRECTIONS
+        .iter()
+        .filter_map(|direction| Some((*direction, get_cell_at(world, neighbour_coordinate(&topology, coordinate, *direction)?)?)))
+        .collect::<Vec<_>>();
+    add_component(world, cell, cell_neighbours(links));
+}
+
+fn get_spatial_index_mut(world: &mut World) -> &mut SpatialIndex {
+    if !has_resource::<SpatialIndex>(world) {
+        add_resource(world, SpatialIndex::default());
+    }
+    get_resource_mut::<SpatialIndex>(world).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CellNeighbours;
+
+    #[test]
+    fn spawning_and_despawning_keeps_links_in_sync() {
+        let mut world = create_world();
+        let centre = spawn_cell(&mut world, (0, 0), true).unwrap();
+        let east = spawn_cell(&mut world, (1, 0), false).unwrap();
+        let south_west = spawn_cell(&mut world, (-1, 1), false).unwrap();
+        assert_eq!(spawn_cell(&mut world, (0, 0), false), Some(centre));
+
+        let neighbours = *get_component::<CellNeighbours>(&world, centre).unwrap();
+        assert_eq!(get_neighbour(&neighbours, Direction::East), Some(east));
+        assert_eq!(get_neighbour(&neighbours, Direction::SouthWest), Some(south_west));
+        assert_eq!(get_neighbour(get_component::<CellNeighbours>(&world, east).unwrap(), Direction::West), Some(centre));
+        assert_eq!(get_neighbour(get_component::<CellNeighbours>(&world, south_west).unwrap(), Direction::NorthEast), Some(centre));
+
+        despawn_cell(&mut world, centre);
+        assert_eq!(get_cell_at(&world, (0, 0)), None);
+        assert_eq!(get_neighbour(get_component::<CellNeighbours>(&world, east).unwrap(), Direction::West), None);
+        assert_eq!(spatial_index_len(&world), 2);
+    }
+
+    #[test]
+    fn rebuilding_wires_loaded_cells() {
+        let mut world = create_world();
+        let cells: Vec<Entity> = [(0, 0), (1, 1), (5, 5)]
+            .iter()
+            .map(|coordinate| {
+                let cell = add_entity(&mut world);
+                add_component(&mut world, cell, cell_position(*coordinate));
+                cell
+            })
+            .collect();
+
+        rebuild_spatial_index(&mut world).unwrap();
+        assert_eq!(get_cell_at(&world, (1, 1)), Some(cells[1]));
+        assert_eq!(get_neighbour(get_component::<CellNeighbours>(&world, cells[0]).unwrap(), Direction::SouthEast), Some(cells[1]));
+        assert_eq!(get_component::<CellNeighbours>(&world, cells[2]), Some(&CellNeighbours::default()));
+    }
+
+    #[test]
+    fn wiring_and_loading_respect_topology() {
+        let mut world = create_world();
+        add_resource(&mut world, Topology::Torus { width: 3, height: 3 });
+        let corner = spawn_cell(&mut world, (0, 0), false).unwrap();
+        let opposite = spawn_cell(&mut world, (2, 2), false).unwrap();
+        assert_eq!(spawn_cell(&mut world, (3, 3), true), Some(corner));
+        assert_eq!(get_neighbour(get_component::<CellNeighbours>(&world, corner).unwrap(), Direction::NorthWest), Some(opposite));
+
+        let mut plane = create_world();
+        add_resource(&mut plane, Topology::Plane { width: 2, height: 2 });
+        assert_eq!(spawn_cell(&mut plane, (2, 0), true), None);
+        let inside = add_entity(&mut plane);
+        add_component(&mut plane, inside, cell_position((1, 1)));
+        let outside = add_entity(&mut plane);
+        add_component(&mut plane, outside, cell_position((5, 1)));
+        rebuild_spatial_index(&mut plane).unwrap();
+        assert_eq!(spatial_index_len(&plane), 1);
+        assert_eq!(get_component::<CellPosition>(&plane, outside).map(grid_coordinate), None);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{ecs::*, CellNeighbours, CellPosition, CellState, GridCoordinate, Topology};
+use crate::{despawn_cell, get_cell_at, iterate_awake_chunks, get_world_neighbourhood, get_world_topology, grid_coordinate, neighbour_coordinate, neighbourhood_directions, spawn_cell};
+
+use super::count_live_neighbours;
+
+pub fn maintain_frontier(world: &mut World) {
+    let topology = get_world_topology(world);
+    if topology != Topology::Unbounded {
+        return;
+    }
+    let directions = neighbourhood_directions(get_world_neighbourhood(world));
+    let missing: Vec<GridCoordinate> = iterate_awake_chunks::<CellPosition, CellState>(world)
+        .flat_map(iterate_chunk::<CellPosition, CellState>)
+        .filter(|(_, state)| state.alive)
+        .flat_map(|(position, _)| {
+            let coordinate = grid_coordinate(position);
+            directions.iter().filter_map(move |direction| neighbour_coordinate(&topology, coordinate, *direction))
+        })
+        .filter(|coordinate| get_cell_at(world, *coordinate).is_none())
+        .collect();
+    for coordinate in missing {
+        spawn_cell(world, coordinate, false);
+    }
+
+    let isolated: Vec<Entity> = iterate_awake_chunks::<CellState, CellNeighbours>(world)
+        .flat_map(iterate_chunk_entities::<CellState, CellNeighbours>)
+        .filter(|(_, state, neighbours)| !state.alive && count_live_neighbours(world, neighbours) == 0)
+        .map(|(cell, _, _)| cell)
+        .collect();
+    for cell in isolated {
+        despawn_cell(world, cell);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use gol_engine::ThreadContext;
+
+    use crate::{create_cell_grid, spatial_index_len, systems::step_life};
+
+    fn live_coordinates(world: &World) -> Vec<GridCoordinate> {
+        let mut live: Vec<GridCoordinate> = iterate_query::<CellPosition, CellState>(world)
+            .filter(|(_, state)| state.alive)
+            .map(|(position, _)| grid_coordinate(position))
+            .collect();
+        live.sort();
+        live
+    }
+
+    #[test]
+    fn glider_travels_on_an_unbounded_board() {
+        let mut world = create_world();
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        for coordinate in glider {
+            spawn_cell(&mut world, coordinate, true);
+        }
+        maintain_frontier(&mut world);
+
+        for generation in 1..=40 {
+            step_life(&ThreadContext::default(), &mut world);
+            maintain_frontier(&mut world);
+            assert!(world_stats(&world).entity_count <= 5 * 9, "generation {}", generation);
+        }
+
+        let mut moved: Vec<GridCoordinate> = glider.iter().map(|(x, y)| (x + 10, y + 10)).collect();
+        moved.sort();
+        assert_eq!(live_coordinates(&world), moved);
+        assert_eq!(spatial_index_len(&world), world_stats(&world).entity_count);
+    }
+
+    #[test]
+    fn dying_patterns_leave_no_cells_behind() {
+        let mut world = create_world();
+        spawn_cell(&mut world, (0, 0), true);
+        spawn_cell(&mut world, (1, 0), true);
+        maintain_frontier(&mut world);
+        assert_eq!(world_stats(&world).entity_count, 12);
+
+        step_life(&ThreadContext::default(), &mut world);
+        maintain_frontier(&mut world);
+        assert_eq!(world_stats(&world).entity_count, 0);
+    }
+
+    #[test]
+    fn bounded_boards_keep_their_grid() {
+        let mut world = create_world();
+        add_resource(&mut world, Topology::Torus { width: 4, height: 4 });
+        create_cell_grid(&mut world, 4, 4);
+        spawn_cell(&mut world, (1, 1), true);
+
+        step_life(&ThreadContext::default(), &mut world);
+        maintain_frontier(&mut world);
+        assert_eq!(world_stats(&world).entity_count, 16);
+        assert_eq!(spatial_index_len(&world), 16);
+    }
+}
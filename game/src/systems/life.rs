@@ -0,0 +1,241 @@
+use std::{
+    collections::*,
+    thread
+};
+
+use gol_engine::*;
+
+use crate::{ecs::*, CellNeighbours, CellPosition, CellState, Rule, Tile};
+use crate::{get_world_neighbourhood, get_world_rule, grid_coordinate, iterate_awake_chunks, neighbour_iter, next_cell_state, update_sleeping_tiles, wake_tiles_around};
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct LifeGeneration(pub u64);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SimulationThreads(pub usize);
+
+impl Default for SimulationThreads {
+    fn default() -> Self {
+        SimulationThreads(thread::available_parallelism().map_or(1, |threads| threads.get()))
+    }
+}
+
+struct Region<'a> {
+    world: &'a World,
+    chunks: Vec<&'a ComponentChunk>,
+}
+
+struct NextStates {
+    worker_index: u32,
+    changes: Vec<(Entity, bool)>,
+}
+
+pub fn get_world_simulation_threads(world: &World) -> usize {
+    get_resource::<SimulationThreads>(world).copied().unwrap_or_default().0.max(1)
+}
+
+pub fn step_life(thread_context: &ThreadContext, world: &mut World) {
+    let rule = get_world_rule(world);
+    let mut tiles = BTreeMap::<Option<(i64, i64)>, Vec<&ComponentChunk>>::default();
+    for chunk in iterate_awake_chunks::<CellState, CellNeighbours>(world) {
+        let tile = get_chunk_shared_component::<Tile>(chunk).map(|tile| (tile.y, tile.x));
+        tiles.entry(tile).or_default().push(chunk);
+    }
+    let stepped_tiles: HashSet<Tile> = tiles.keys().flatten().map(|(y, x)| Tile { x: *x, y: *y }).collect();
+    let regions = partition_regions(world, tiles, get_world_simulation_threads(world));
+
+    let next_states: Vec<(Entity, bool)> = thread::scope(|scope| {
+        let mut regions = regions.into_iter().enumerate();
+        let first = regions.next();
+        let workers: Vec<_> = regions
+            .map(|(index, region)| {
+                let worker_index = thread_context.worker_index + index as u32;
+                scope.spawn(move || step_region(&worker_thread_context(worker_index), region, &rule))
+            })
+            .collect();
+
+        let mut buffers = Vec::<NextStates>::default();
+        buffers.extend(first.map(|(_, region)| step_region(thread_context, region, &rule)));
+        buffers.extend(workers.into_iter().map(|worker| worker.join().unwrap()));
+        buffers.sort_by_key(|buffer| buffer.worker_index);
+        buffers.into_iter().flat_map(|buffer| buffer.changes).collect()
+    });
+
+    let mut changed = Vec::with_capacity(next_states.len());
+    for (entity, alive) in next_states {
+        get_component_mut::<CellState>(world, entity).unwrap().alive = alive;
+        changed.extend(get_component::<CellPosition>(world, entity).map(grid_coordinate));
+    }
+    update_sleeping_tiles(world, &stepped_tiles, &changed);
+
+    let generation = get_resource::<LifeGeneration>(world).copied().unwrap_or_default();
+    add_resource(world, LifeGeneration(generation.0 + 1));
+}
+
+fn partition_regions<'a>(world: &'a World, tiles: BTreeMap<Option<(i64, i64)>, Vec<&'a ComponentChunk>>, threads: usize) -> Vec<Region<'a>> {
+    let cells: usize = tiles.values().flatten().map(|chunk| chunk_entity_count(chunk)).sum();
+    let cells_per_region = cells.div_ceil(threads).max(1);
+    let mut regions = Vec::<Region>::default();
+    let mut region_cells = 0;
+    for chunks in tiles.into_values() {
+        if regions.is_empty() || region_cells >= cells_per_region {
+            regions.push(Region { world, chunks: Vec::default() });
+            region_cells = 0;
+        }
+        region_cells += chunks.iter().map(|chunk| chunk_entity_count(chunk)).sum::<usize>();
+        regions.last_mut().unwrap().chunks.extend(chunks);
+    }
+    regions
+}
+
+fn step_region(thread_context: &ThreadContext, region: Region, rule: &Rule) -> NextStates {
+    let changes = region.chunks
+        .into_iter()
+        .flat_map(iterate_chunk_entities::<CellState, CellNeighbours>)
+        .filter_map(|(entity, state, neighbours)| {
+            let alive = next_cell_state(rule, state.alive, count_live_neighbours(region.world, neighbours));
+            (alive != state.alive).then_some((entity, alive))
+        })
+        .collect();
+    NextStates { worker_index: thread_context.worker_index, changes }
+}
+
+pub fn life_generation(world: &World) -> u64 {
+    get_resource::<LifeGeneration>(world).copied().unwrap_or_default().0
+}
+
+pub fn count_live_neighbours(world: &World, neighbours: &CellNeighbours) -> usize {
+    neighbour_iter(neighbours, get_world_neighbourhood(world))
+        .filter(|neighbour| is_cell_alive(world, *neighbour))
+        .count()
+}
+
+pub fn is_cell_alive(world: &World, cell: Entity) -> bool {
+    get_component::<CellState>(world, cell).is_some_and(|state| state.alive)
+}
+
+pub fn set_cell_alive(world: &mut World, cell: Entity, alive: bool) {
+    add_component(world, cell, CellState { alive });
+    if let Some(coordinate) = get_component::<CellPosition>(world, cell).map(grid_coordinate) {
+        wake_tiles_around(world, coordinate);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{create_cell_grid, parse_rule, seed_soup, LifeBackend, Neighbourhood, Topology, TILE_SIZE};
+
+    const SIZE: usize = 8;
+
+    fn create_board(live_cells: &[(usize, usize)]) -> (World, Vec<Entity>) {
+        let mut world = create_world();
+        let cells = create_cell_grid(&mut world, SIZE, SIZE);
+        for (x, y) in live_cells {
+            set_cell_alive(&mut world, cells[y * SIZE + x], true);
+        }
+        (world, cells)
+    }
+
+    fn live_cells(world: &World, cells: &[Entity]) -> Vec<(usize, usize)> {
+        cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| is_cell_alive(world, **cell))
+            .map(|(index, _)| (index % SIZE, index / SIZE))
+            .collect()
+    }
+
+    fn sorted(mut cells: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+        cells.sort_by_key(|(x, y)| (*y, *x));
+        cells
+    }
+
+    #[test]
+    fn blinker_has_period_two() {
+        let horizontal = vec!((2, 3), (3, 3), (4, 3));
+        let vertical = vec!((3, 2), (3, 3), (3, 4));
+        let (mut world, cells) = create_board(&horizontal);
+
+        step_life(&ThreadContext::default(), &mut world);
+        assert_eq!(live_cells(&world, &cells), sorted(vertical));
+        step_life(&ThreadContext::default(), &mut world);
+        assert_eq!(live_cells(&world, &cells), sorted(horizontal));
+        assert_eq!(life_generation(&world), 2);
+    }
+
+    #[test]
+    fn glider_moves_diagonally_every_four_generations() {
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let (mut world, cells) = create_board(&glider);
+
+        for offset in 1..=3 {
+            for _ in 0..4 {
+                step_life(&ThreadContext::default(), &mut world);
+            }
+            let moved = glider.iter().map(|(x, y)| (x + offset, y + offset)).collect();
+            assert_eq!(live_cells(&world, &cells), sorted(moved));
+        }
+    }
+
+    #[test]
+    fn von_neumann_neighbourhood_ignores_diagonals() {
+        let corners = [(2, 2), (4, 2), (2, 4)];
+        let (mut moore, cells) = create_board(&corners);
+        step_life(&ThreadContext::default(), &mut moore);
+        assert_eq!(live_cells(&moore, &cells), vec!((3, 3)));
+
+        let (mut von_neumann, cells) = create_board(&corners);
+        add_resource(&mut von_neumann, Neighbourhood::VonNeumann);
+        step_life(&ThreadContext::default(), &mut von_neumann);
+        assert_eq!(live_cells(&von_neumann, &cells), vec!());
+    }
+
+    #[test]
+    fn step_reads_rule_from_world() {
+        let ring = [(2, 2), (3, 2), (4, 2), (2, 4), (3, 4), (4, 4)];
+        let (mut conway, cells) = create_board(&ring);
+        step_life(&ThreadContext::default(), &mut conway);
+        assert!(!is_cell_alive(&conway, cells[3 * SIZE + 3]));
+
+        let (mut high_life, cells) = create_board(&ring);
+        add_resource(&mut high_life, parse_rule("B36/S23").unwrap());
+        step_life(&ThreadContext::default(), &mut high_life);
+        assert!(is_cell_alive(&high_life, cells[3 * SIZE + 3]));
+    }
+
+    #[test]
+    fn glider_wraps_around_a_torus() {
+        let mut world = create_world();
+        add_resource(&mut world, Topology::Torus { width: SIZE as i64, height: SIZE as i64 });
+        let cells = create_cell_grid(&mut world, SIZE, SIZE);
+        let glider = vec!((1, 0), (2, 1), (0, 2), (1, 2), (2, 2));
+        for (x, y) in &glider {
+            set_cell_alive(&mut world, cells[y * SIZE + x], true);
+        }
+
+        for _ in 0..4 * SIZE {
+            step_life(&ThreadContext::default(), &mut world);
+        }
+        assert_eq!(live_cells(&world, &cells), sorted(glider));
+    }
+
+    #[test]
+    fn threaded_steps_match_single_threaded_steps() {
+        let create_soup = |threads: usize| {
+            let mut world = create_world();
+            add_resource(&mut world, SimulationThreads(threads));
+            seed_soup(&mut world, TILE_SIZE - 20..TILE_SIZE + 20, -12..12, 0x9e37_79b9_7f4a_7c15);
+            world
+        };
+        let mut single = create_soup(1);
+        let mut threaded = create_soup(4);
+
+        for generation in 0..16 {
+            assert_eq!(single.live_cells(), threaded.live_cells(), "generation {}", generation);
+            single.step_generation();
+            threaded.step_generation();
+        }
+        assert_eq!(life_generation(&single), life_generation(&threaded));
+    }
+}
@@ -1,10 +1,31 @@
-use crate::{ecs::*, CellPosition, CellNeighbours};
+mod schedule;
+mod life;
+mod frontier;
+
+pub use schedule::*;
+pub use life::*;
+pub use frontier::*;
+
+use gol_engine::ThreadContext;
+
+use crate::ecs::*;
+
+pub const SIMULATION_HZ: f32 = 10.0;
+
+pub fn create_game_schedule() -> Schedule {
+    let mut schedule = schedule();
+    set_stage_run_criteria(&mut schedule, Stage::Simulate, RunCriteria::FixedTimestep { hz: SIMULATION_HZ });
+    add_system(&mut schedule, Stage::Simulate, step_life);
+    add_system(&mut schedule, Stage::Simulate, |_, world| maintain_frontier(world));
+    schedule
+}
 
 pub fn run_systems(
-    world: &mut World
+    thread_context: &ThreadContext,
+    world: &mut World,
+    delta_time_for_frame: f32
 ) {
-    for (position, neighbours) in iterate_query::<CellPosition, CellNeighbours>(world) {
-        println!("{:?}", position);
-        println!("{:?}", neighbours);
-    }
-}
\ No newline at end of file
+    let mut schedule = remove_resource::<Schedule>(world).unwrap_or_else(create_game_schedule);
+    run_schedule(&mut schedule, thread_context, world, delta_time_for_frame);
+    add_resource(world, schedule);
+}
@@ -0,0 +1,197 @@
+use gol_engine::ThreadContext;
+
+use crate::ecs::*;
+
+pub type System = fn(&ThreadContext, &mut World);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Input,
+    Simulate,
+    Render,
+    Audio,
+}
+
+pub const STAGES: [Stage; 4] = [Stage::Input, Stage::Simulate, Stage::Render, Stage::Audio];
+
+pub const MAX_FIXED_STEPS_PER_FRAME: u32 = 8;
+
+#[derive(Copy, Clone)]
+pub enum RunCriteria {
+    EveryFrame,
+    FixedTimestep { hz: f32 },
+    EveryNthFrame(u64),
+    ResourceFlag(fn(&World) -> bool),
+}
+
+pub trait StageFlag: Resource {
+    fn is_set(&self) -> bool;
+}
+
+pub fn run_when_flag_set<F: StageFlag>() -> RunCriteria {
+    RunCriteria::ResourceFlag(stage_flag_is_set::<F>)
+}
+
+fn stage_flag_is_set<F: StageFlag>(world: &World) -> bool {
+    get_resource::<F>(world).is_some_and(StageFlag::is_set)
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct StageTime {
+    pub delta_time: f32,
+    pub frame: u64,
+}
+
+struct ScheduledStage {
+    stage: Stage,
+    criteria: RunCriteria,
+    systems: Vec<System>,
+    accumulator: f32,
+}
+
+pub struct Schedule {
+    stages: Vec<ScheduledStage>,
+    frame: u64,
+}
+
+pub fn schedule() -> Schedule {
+    Schedule {
+        stages: STAGES
+            .iter()
+            .map(|stage| ScheduledStage { stage: *stage, criteria: RunCriteria::EveryFrame, systems: vec!(), accumulator: 0.0 })
+            .collect(),
+        frame: 0,
+    }
+}
+
+pub fn set_stage_run_criteria(schedule: &mut Schedule, stage: Stage, criteria: RunCriteria) {
+    let scheduled = get_scheduled_stage_mut(schedule, stage);
+    scheduled.criteria = criteria;
+    scheduled.accumulator = 0.0;
+}
+
+pub fn add_system(schedule: &mut Schedule, stage: Stage, system: System) {
+    get_scheduled_stage_mut(schedule, stage).systems.push(system);
+}
+
+pub fn schedule_frame(schedule: &Schedule) -> u64 {
+    schedule.frame
+}
+
+fn get_scheduled_stage_mut(schedule: &mut Schedule, stage: Stage) -> &mut ScheduledStage {
+    schedule.stages.iter_mut().find(|scheduled| scheduled.stage == stage).unwrap()
+}
+
+pub fn run_schedule(schedule: &mut Schedule, thread_context: &ThreadContext, world: &mut World, delta_time_for_frame: f32) {
+    let frame = schedule.frame;
+    for scheduled in &mut schedule.stages {
+        let (runs, delta_time) = stage_runs_for_frame(scheduled, world, frame, delta_time_for_frame);
+        for _ in 0..runs {
+            add_resource(world, StageTime { delta_time, frame });
+            for system in &scheduled.systems {
+                system(thread_context, world);
+            }
+        }
+    }
+    schedule.frame += 1;
+}
+
+fn stage_runs_for_frame(scheduled: &mut ScheduledStage, world: &World, frame: u64, delta_time_for_frame: f32) -> (u32, f32) {
+    match scheduled.criteria {
+        RunCriteria::EveryFrame => (1, delta_time_for_frame),
+        RunCriteria::FixedTimestep { hz } => {
+            let timestep = 1.0 / hz;
+            scheduled.accumulator += delta_time_for_frame;
+            let mut runs = 0;
+            while scheduled.accumulator >= timestep && runs < MAX_FIXED_STEPS_PER_FRAME {
+                scheduled.accumulator -= timestep;
+                runs += 1;
+            }
+            if runs == MAX_FIXED_STEPS_PER_FRAME {
+                scheduled.accumulator = scheduled.accumulator.min(timestep);
+            }
+            (runs, timestep)
+        },
+        RunCriteria::EveryNthFrame(n) => (frame.is_multiple_of(n) as u32, delta_time_for_frame * n as f32),
+        RunCriteria::ResourceFlag(is_set) => (is_set(world) as u32, delta_time_for_frame),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Default)]
+    struct Counts {
+        input: u32,
+        simulate: u32,
+        render: u32,
+        audio: u32,
+        simulate_delta_time: f32,
+    }
+
+    struct Paused(bool);
+
+    impl StageFlag for Paused {
+        fn is_set(&self) -> bool {
+            !self.0
+        }
+    }
+
+    fn count_input(_thread_context: &ThreadContext, world: &mut World) { get_resource_mut::<Counts>(world).unwrap().input += 1; }
+    fn count_simulate(_thread_context: &ThreadContext, world: &mut World) {
+        let delta_time = get_resource::<StageTime>(world).unwrap().delta_time;
+        let counts = get_resource_mut::<Counts>(world).unwrap();
+        counts.simulate += 1;
+        counts.simulate_delta_time = delta_time;
+    }
+    fn count_render(_thread_context: &ThreadContext, world: &mut World) { get_resource_mut::<Counts>(world).unwrap().render += 1; }
+    fn count_audio(_thread_context: &ThreadContext, world: &mut World) { get_resource_mut::<Counts>(world).unwrap().audio += 1; }
+
+    #[test]
+    fn stages_follow_their_run_criteria() {
+        let mut world = create_world();
+        add_resource(&mut world, Counts::default());
+        add_resource(&mut world, Paused(false));
+
+        let mut schedule = schedule();
+        add_system(&mut schedule, Stage::Input, count_input);
+        add_system(&mut schedule, Stage::Simulate, count_simulate);
+        add_system(&mut schedule, Stage::Render, count_render);
+        add_system(&mut schedule, Stage::Audio, count_audio);
+        set_stage_run_criteria(&mut schedule, Stage::Simulate, RunCriteria::FixedTimestep { hz: 120.0 });
+        set_stage_run_criteria(&mut schedule, Stage::Render, RunCriteria::EveryNthFrame(3));
+        set_stage_run_criteria(&mut schedule, Stage::Audio, run_when_flag_set::<Paused>());
+
+        for frame in 0..30 {
+            if frame == 15 {
+                add_resource(&mut world, Paused(true));
+            }
+            run_schedule(&mut schedule, &ThreadContext::default(), &mut world, 1.0 / 30.0);
+        }
+
+        let counts = get_resource::<Counts>(&world).unwrap();
+        assert_eq!(counts.input, 30);
+        assert!((119..=120).contains(&counts.simulate));
+        assert_eq!(counts.render, 10);
+        assert_eq!(counts.audio, 15);
+        assert_eq!(get_resource::<StageTime>(&world).unwrap().frame, 29);
+    }
+
+    #[test]
+    fn fixed_timestep_catches_up_within_limit() {
+        let mut world = create_world();
+        add_resource(&mut world, Counts::default());
+        let mut schedule = schedule();
+        add_system(&mut schedule, Stage::Simulate, count_simulate);
+        set_stage_run_criteria(&mut schedule, Stage::Simulate, RunCriteria::FixedTimestep { hz: 10.0 });
+
+        run_schedule(&mut schedule, &ThreadContext::default(), &mut world, 0.06);
+        assert_eq!(get_resource::<Counts>(&world).unwrap().simulate, 0);
+        run_schedule(&mut schedule, &ThreadContext::default(), &mut world, 0.06);
+        assert_eq!(get_resource::<Counts>(&world).unwrap().simulate, 1);
+        run_schedule(&mut schedule, &ThreadContext::default(), &mut world, 10.0);
+        assert_eq!(get_resource::<Counts>(&world).unwrap().simulate, 1 + MAX_FIXED_STEPS_PER_FRAME);
+        assert_eq!(get_resource::<Counts>(&world).unwrap().simulate_delta_time, 0.1);
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::*;
+
+use crate::{ecs::*, get_world_neighbourhood, get_world_topology, neighbour_coordinate, neighbourhood_directions, CellPosition, CellState, GridCoordinate, MOORE_DIRECTIONS};
+
+pub const TILE_SIZE: i64 = 64;
+pub const GENERATIONS_BEFORE_SLEEP: u32 = 2;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Tile {
+    pub x: i64,
+    pub y: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SleepingTiles {
+    asleep: HashSet<Tile>,
+    unchanged_generations: HashMap<Tile, u32>,
+}
+
+pub fn tile_of(coordinate: GridCoordinate) -> Tile {
+    Tile { x: coordinate.0.div_euclid(TILE_SIZE), y: coordinate.1.div_euclid(TILE_SIZE) }
+}
+
+pub fn register_tile_component(registry: &mut ComponentRegistry) {
+    register_component::<Tile>(registry, "Tile");
+    register_shared_component_serialization::<Tile>(registry, save_tile, load_tile);
+}
+
+fn save_tile(tile: &Tile, writer: &mut SnapshotWriter) {
+    writer.write_u64(tile.x as u64);
+    writer.write_u64(tile.y as u64);
+}
+
+fn load_tile(reader: &mut SnapshotReader) -> Result<Tile, SnapshotError> {
+    Ok(Tile { x: reader.read_u64()? as i64, y: reader.read_u64()? as i64 })
+}
+
+pub fn is_tile_asleep(world: &World, tile: Tile) -> bool {
+    get_resource::<SleepingTiles>(world).is_some_and(|tiles| tiles.asleep.contains(&tile))
+}
+
+pub fn sleeping_tile_count(world: &World) -> usize {
+    get_resource::<SleepingTiles>(world).map_or(0, |tiles| tiles.asleep.len())
+}
+
+pub fn wake_tile(world: &mut World, tile: Tile) {
+    if let Some(tiles) = get_resource_mut::<SleepingTiles>(world) {
+        tiles.asleep.remove(&tile);
+        tiles.unchanged_generations.remove(&tile);
+    }
+}
+
+pub fn wake_tiles_around(world: &mut World, coordinate: GridCoordinate) {
+    let topology = get_world_topology(world);
+    wake_tile(world, tile_of(coordinate));
+    for direction in MOORE_DIRECTIONS {
+        if let Some(neighbour) = neighbour_coordinate(&topology, coordinate, direction) {
+            wake_tile(world, tile_of(neighbour));
+        }
+    }
+}
+
+pub fn wake_all_tiles(world: &mut World) {
+    add_resource(world, SleepingTiles::default());
+}
+
+pub fn iterate_awake_chunks<'a, A:Component, B:Component>(world: &'a World) -> impl Iterator<Item = &'a ComponentChunk> + 'a {
+    iterate_query_chunks::<A, B>(world)
+        .filter(|chunk| get_chunk_shared_component::<Tile>(chunk).is_none_or(|tile| !is_tile_asleep(world, *tile)))
+}
+
+pub fn update_sleeping_tiles(world: &mut World, stepped: &HashSet<Tile>, changed: &[GridCoordinate]) {
+    let topology = get_world_topology(world);
+    let directions = neighbourhood_directions(get_world_neighbourhood(world));
+    let mut changed_tiles = HashSet::<Tile>::default();
+    let mut woken = HashSet::<Tile>::default();
+    for coordinate in changed {
+        let tile = tile_of(*coordinate);
+        changed_tiles.insert(tile);
+        woken.extend(
+            directions
+                .iter()
+                .filter_map(|direction| neighbour_coordinate(&topology, *coordinate, *direction))
+                .map(tile_of)
+                .filter(|neighbour| *neighbour != tile)
+        );
+    }
+
+    let occupied: HashSet<Tile> = iterate_query_chunks::<CellPosition, CellState>(world)
+        .filter_map(|chunk| get_chunk_shared_component::<Tile>(chunk).copied())
+        .collect();
+
+    if !has_resource::<SleepingTiles>(world) {
+        add_resource(world, SleepingTiles::default());
+    }
+    let tiles = get_resource_mut::<SleepingTiles>(world).unwrap();
+    tiles.asleep.retain(|tile| occupied.contains(tile));
+    tiles.unchanged_generations.retain(|tile, _| occupied.contains(tile));
+    for tile in stepped {
+        if changed_tiles.contains(tile) {
+            tiles.unchanged_generations.remove(tile);
+            continue;
+        }
+        let unchanged = tiles.unchanged_generations.entry(*tile).or_default();
+        *unchanged += 1;
+        if *unchanged >= GENERATIONS_BEFORE_SLEEP {
+            tiles.unchanged_generations.remove(tile);
+            tiles.asleep.insert(*tile);
+        }
+    }
+    for tile in woken {
+        tiles.asleep.remove(&tile);
+        tiles.unchanged_generations.remove(&tile);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{bit_grid, conway_rule, spawn_cell, LifeBackend, Neighbourhood, Topology};
+
+    const BLOCK: [GridCoordinate; 4] = [(0, 0), (1, 0), (0, 1), (1, 1)];
+    const GLIDER: [GridCoordinate; 5] = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+
+    #[test]
+    fn still_lifes_fall_asleep() {
+        let mut world = create_world();
+        for tile in 0..4 {
+            for (x, y) in BLOCK {
+                spawn_cell(&mut world, (x + tile * TILE_SIZE + 10, y - 10), true);
+            }
+        }
+        world.step_generation();
+        assert_eq!(sleeping_tile_count(&world), 0);
+        world.step_generation();
+        assert_eq!(sleeping_tile_count(&world), 4);
+        assert!(is_tile_asleep(&world, Tile { x: 3, y: -1 }));
+
+        spawn_cell(&mut world, (TILE_SIZE - 1, -1), true);
+        assert!(!is_tile_asleep(&world, Tile { x: 0, y: -1 }));
+        assert!(!is_tile_asleep(&world, Tile { x: 1, y: -1 }));
+        assert!(!is_tile_asleep(&world, Tile { x: 1, y: 0 }));
+        assert!(is_tile_asleep(&world, Tile { x: 2, y: -1 }));
+    }
+
+    #[test]
+    fn changes_across_a_border_wake_sleeping_tiles() {
+        let mut world = create_world();
+        let mut dense = bit_grid(Topology::Plane { width: 256, height: 256 }, conway_rule(), Neighbourhood::Moore).unwrap();
+        let block = BLOCK.iter().map(|(x, y)| (x + TILE_SIZE + 2, y + TILE_SIZE + 2));
+        let glider = GLIDER.iter().map(|(x, y)| (x + TILE_SIZE - 14, y + TILE_SIZE - 13));
+        for coordinate in block.chain(glider) {
+            world.set_cell(coordinate, true);
+            dense.set_cell(coordinate, true);
+        }
+
+        for generation in 0..80 {
+            assert_eq!(world.live_cells(), dense.live_cells(), "generation {}", generation);
+            if generation == 4 {
+                assert!(is_tile_asleep(&world, Tile { x: 1, y: 1 }));
+            }
+            world.step_generation();
+            dense.step_generation();
+        }
+    }
+
+    #[test]
+    fn tiles_left_behind_are_forgotten() {
+        let mut world = create_world();
+        for coordinate in GLIDER {
+            spawn_cell(&mut world, (coordinate.0 + TILE_SIZE - 8, coordinate.1 + TILE_SIZE - 8), true);
+        }
+        world.step_generation();
+        let archetype_count = world_stats(&world).archetypes.len();
+
+        for _ in 0..4 * TILE_SIZE * 2 {
+            world.step_generation();
+        }
+        assert_eq!(world.live_cells().len(), 5);
+        assert!(world_stats(&world).archetypes.len() <= archetype_count + 4, "{} archetypes", world_stats(&world).archetypes.len());
+        assert_eq!(sleeping_tile_count(&world), 0);
+    }
+}
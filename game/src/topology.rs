@@ -0,0 +1,234 @@
+use std::fmt;
+
+use crate::{ecs::*, GridCoordinate};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Twist {
+    Width,
+    Height,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Topology {
+    #[default]
+    Unbounded,
+    Plane { width: i64, height: i64 },
+    Torus { width: i64, height: i64 },
+    KleinBottle { width: i64, height: i64, twist: Twist },
+    CrossSurface { width: i64, height: i64 },
+}
+
+pub fn get_world_topology(world: &World) -> Topology {
+    get_resource::<Topology>(world).copied().unwrap_or_default()
+}
+
+pub fn register_topology_resource(registry: &mut ComponentRegistry) {
+    register_resource::<Topology>(registry, "Topology", save_topology, load_topology);
+    register_resource_clone::<Topology>(registry);
+}
+
+fn save_topology(topology: &Topology, writer: &mut SnapshotWriter) {
+    writer.write_str(&topology.to_string());
+}
+
+fn load_topology(reader: &mut SnapshotReader) -> Result<Topology, SnapshotError> {
+    match reader.read_str()? {
+        "" => Ok(Topology::Unbounded),
+        text => text
+            .strip_prefix(':')
+            .and_then(|text| parse_topology(text).ok())
+            .ok_or(SnapshotError::InvalidValue("Topology")),
+    }
+}
+
+pub fn topology_size(topology: &Topology) -> Option<(i64, i64)> {
+    match *topology {
+        Topology::Unbounded => None,
+        Topology::Plane { width, height }
+            | Topology::Torus { width, height }
+            | Topology::KleinBottle { width, height, .. }
+            | Topology::CrossSurface { width, height } => Some((width, height)),
+    }
+}
+
+pub fn resolve_coordinate(topology: &Topology, coordinate: GridCoordinate) -> Option<GridCoordinate> {
+    let (x, y) = coordinate;
+    match *topology {
+        Topology::Unbounded => Some(coordinate),
+        Topology::Plane { width, height } => ((0..width).contains(&x) && (0..height).contains(&y)).then_some(coordinate),
+        Topology::Torus { width, height } => Some((x.rem_euclid(width), y.rem_euclid(height))),
+        Topology::KleinBottle { width, height, twist: Twist::Width } => Some(wrap_twisted(x, y, width, height)),
+        Topology::KleinBottle { width, height, twist: Twist::Height } => {
+            let (y, x) = wrap_twisted(y, x, height, width);
+            Some((x, y))
+        },
+        Topology::CrossSurface { width, height } => {
+            let mirrored_x = if y.div_euclid(height) % 2 != 0 { width - 1 - x } else { x };
+            let mirrored_y = if x.div_euclid(width) % 2 != 0 { height - 1 - y } else { y };
+            Some((mirrored_x.rem_euclid(width), mirrored_y.rem_euclid(height)))
+        },
+    }
+}
+
+fn wrap_twisted(mirrored: i64, wrapped: i64, mirrored_size: i64, wrapped_size: i64) -> (i64, i64) {
+    let mirrored = if wrapped.div_euclid(wrapped_size) % 2 != 0 { mirrored_size - 1 - mirrored } else { mirrored };
+    (mirrored.rem_euclid(mirrored_size), wrapped.rem_euclid(wrapped_size))
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Topology::Unbounded => Ok(()),
+            Topology::Plane { width, height } => write!(f, ":P{},{}", width, height),
+            Topology::Torus { width, height } => write!(f, ":T{},{}", width, height),
+            Topology::KleinBottle { width, height, twist: Twist::Width } => write!(f, ":K{}*,{}", width, height),
+            Topology::KleinBottle { width, height, twist: Twist::Height } => write!(f, ":K{},{}*", width, height),
+            Topology::CrossSurface { width, height } => write!(f, ":C{},{}", width, height),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopologyErrorKind {
+    UnknownTopology(char),
+    MissingSize,
+    InvalidSize(String),
+    UnexpectedTwist,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopologyError {
+    pub column: usize,
+    pub kind: TopologyErrorKind,
+}
+
+fn topology_error(column: usize, kind: TopologyErrorKind) -> TopologyError {
+    TopologyError { column, kind }
+}
+
+impl fmt::Display for TopologyErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TopologyErrorKind::UnknownTopology(character) => write!(f, "unknown topology '{}', expected P, T, K or C", character),
+            TopologyErrorKind::MissingSize => write!(f, "expected a 'width,height' size"),
+            TopologyErrorKind::InvalidSize(size) => write!(f, "'{}' is not a positive size", size),
+            TopologyErrorKind::UnexpectedTwist => write!(f, "only a Klein bottle takes a twisted '*' edge, and only on one side"),
+        }
+    }
+}
+
+impl fmt::Display for TopologyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.kind)
+    }
+}
+
+pub fn parse_topology(text: &str) -> Result<Topology, TopologyError> {
+    let mut characters = text.chars();
+    let kind = match characters.next() {
+        Some(kind) => kind.to_ascii_uppercase(),
+        None => return Err(topology_error(1, TopologyErrorKind::MissingSize)),
+    };
+    if !matches!(kind, 'P' | 'T' | 'K' | 'C') {
+        return Err(topology_error(1, TopologyErrorKind::UnknownTopology(kind)));
+    }
+
+    let size = &text[1..];
+    let comma = match size.find(',') {
+        Some(comma) => comma,
+        None => return Err(topology_error(text.chars().count() + 1, TopologyErrorKind::MissingSize)),
+    };
+    let (width, width_twisted) = parse_topology_size(&size[..comma], 2)?;
+    let (height, height_twisted) = parse_topology_size(&size[comma + 1..], comma + 3)?;
+
+    let twist = match (width_twisted, height_twisted) {
+        (false, false) => None,
+        (true, false) => Some(Twist::Width),
+        (false, true) => Some(Twist::Height),
+        (true, true) => return Err(topology_error(text.chars().count(), TopologyErrorKind::UnexpectedTwist)),
+    };
+    match (kind, twist) {
+        ('P', None) => Ok(Topology::Plane { width, height }),
+        ('T', None) => Ok(Topology::Torus { width, height }),
+        ('C', None) => Ok(Topology::CrossSurface { width, height }),
+        ('K', Some(twist)) => Ok(Topology::KleinBottle { width, height, twist }),
+        ('K', None) => Ok(Topology::KleinBottle { width, height, twist: Twist::Width }),
+        _ => Err(topology_error(text.find('*').unwrap() + 1, TopologyErrorKind::UnexpectedTwist)),
+    }
+}
+
+fn parse_topology_size(text: &str, column: usize) -> Result<(i64, bool), TopologyError> {
+    let twisted = text.ends_with('*');
+    let digits = text.trim_end_matches('*');
+    match digits.parse::<i64>() {
+        Ok(size) if size > 0 && !digits.starts_with('+') => Ok((size, twisted)),
+        _ => Err(topology_error(column, TopologyErrorKind::InvalidSize(text.to_string()))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{get_cell_at, get_neighbour, neighbour_coordinate, spawn_cell, CellNeighbours, Direction, MOORE_DIRECTIONS};
+
+    #[test]
+    fn topologies_resolve_coordinates() {
+        let plane = Topology::Plane { width: 4, height: 3 };
+        assert_eq!(resolve_coordinate(&plane, (3, 2)), Some((3, 2)));
+        assert_eq!(resolve_coordinate(&plane, (4, 2)), None);
+
+        let torus = Topology::Torus { width: 4, height: 3 };
+        assert_eq!(resolve_coordinate(&torus, (-1, 3)), Some((3, 0)));
+
+        let klein = Topology::KleinBottle { width: 4, height: 3, twist: Twist::Width };
+        assert_eq!(resolve_coordinate(&klein, (-1, 1)), Some((3, 1)));
+        assert_eq!(resolve_coordinate(&klein, (0, -1)), Some((3, 2)));
+
+        let cross = Topology::CrossSurface { width: 4, height: 3 };
+        assert_eq!(resolve_coordinate(&cross, (0, 3)), Some((3, 0)));
+        assert_eq!(resolve_coordinate(&cross, (4, 0)), Some((0, 2)));
+        assert_eq!(resolve_coordinate(&Topology::Unbounded, (-7, 9)), Some((-7, 9)));
+    }
+
+    #[test]
+    fn topology_suffixes_round_trip() {
+        for text in ["P100,80", "T100,80", "K100*,80", "K100,80*", "C30,20"] {
+            assert_eq!(parse_topology(text).unwrap().to_string(), format!(":{}", text));
+        }
+        assert_eq!(parse_topology("X1,1"), Err(topology_error(1, TopologyErrorKind::UnknownTopology('X'))));
+        assert_eq!(parse_topology("T100"), Err(topology_error(5, TopologyErrorKind::MissingSize)));
+        assert_eq!(parse_topology("T100,0"), Err(topology_error(6, TopologyErrorKind::InvalidSize("0".to_string()))));
+        assert_eq!(parse_topology("T100*,80"), Err(topology_error(5, TopologyErrorKind::UnexpectedTwist)));
+    }
+
+    #[test]
+    fn cells_link_across_twisted_edges() {
+        for topology in [parse_topology("K4*,3").unwrap(), parse_topology("K4,3*").unwrap(), parse_topology("C4,3").unwrap()] {
+            let mut world = create_world();
+            add_resource(&mut world, topology);
+            for y in 0..3 {
+                for x in 0..4 {
+                    spawn_cell(&mut world, (x, y), false);
+                }
+            }
+
+            for y in 0..3 {
+                for x in 0..4 {
+                    let cell = get_cell_at(&world, (x, y)).unwrap();
+                    let neighbours = get_component::<CellNeighbours>(&world, cell).unwrap();
+                    for direction in MOORE_DIRECTIONS {
+                        let expected = neighbour_coordinate(&topology, (x, y), direction).and_then(|neighbour| get_cell_at(&world, neighbour));
+                        assert_eq!(get_neighbour(neighbours, direction), expected, "{} ({}, {}) {:?}", topology, x, y, direction);
+                    }
+                }
+            }
+        }
+
+        let mut klein = create_world();
+        add_resource(&mut klein, parse_topology("K4*,3").unwrap());
+        let corner = spawn_cell(&mut klein, (0, 0), false).unwrap();
+        let bottom = spawn_cell(&mut klein, (0, 2), false).unwrap();
+        assert_eq!(get_neighbour(get_component::<CellNeighbours>(&klein, bottom).unwrap(), Direction::SouthWest), Some(corner));
+        assert_eq!(get_neighbour(get_component::<CellNeighbours>(&klein, bottom).unwrap(), Direction::SouthEast), None);
+    }
+}
@@ -16,4 +16,9 @@ pub fn debug_platform_free_file_memory(memory: *mut c_void) {
 #[cfg(feature="gol-internal")]
 pub fn debug_platform_write_entire_file(file_name: &str, memory_size: u32, memory: *mut c_void) {
     todo!();
-}
\ No newline at end of file
+}
+
+#[cfg(feature="gol-internal")]
+pub fn debug_platform_log_message(_thread_context: &mut ThreadContext, message: &str) {
+    eprintln!("{}", message);
+}
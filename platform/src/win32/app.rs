@@ -152,6 +152,7 @@ impl App {
                         debug_platform_read_entire_file_func: Box::new(debug_platform_read_entire_file), 
                         debug_platform_write_entire_file_func: Box::new(debug_platform_write_entire_file), 
                         debug_platform_free_file_memory_func: Box::new(debug_platform_free_file_memory),
+                        debug_platform_log_message_func: Box::new(debug_platform_log_message),
                     };
                                         
                     let mut state = Win32State::new();
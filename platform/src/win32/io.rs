@@ -3,5 +3,5 @@ pub fn build_target_file_path_name(file_name: &str) -> String {
 }
 
 pub fn build_data_file_name(file_name: &str) -> String {
-    format!("..\\..\\data\\{}", file_name)
+    format!("..\\data\\{}", file_name)
 }
\ No newline at end of file
@@ -112,4 +112,9 @@ pub fn debug_platform_free_file_memory(_thread_context: &mut ThreadContext, memo
             VirtualFree(memory, 0, MEM_RELEASE);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(feature="gol-internal")]
+pub fn debug_platform_log_message(_thread_context: &mut ThreadContext, message: &str) {
+    println!("{}", message);
+}